@@ -1,6 +1,189 @@
 use serde::{Deserialize, Serialize};
 use anyhow::{Result, anyhow};
 use std::collections::HashMap;
+use std::sync::Arc;
+use futures_util::stream::{BoxStream, StreamExt};
+use tokio::sync::RwLock;
+use tracing::{error, info, instrument};
+
+use crate::powerautomation::PowerAutomationCore;
+
+const MAX_TOOL_CALL_STEPS: usize = 8;
+
+/// Fallback order `generate_code` routes through when the caller doesn't pin
+/// a specific model.
+const CODE_GENERATION_CANDIDATES: &[&str] = &["claude-3-5-sonnet", "gpt-4", "claude-3-haiku", "gpt-3.5-turbo", "gemini-pro"];
+/// Fallback order `analyze_code`/`explain_code` route through when the caller
+/// doesn't pin a specific model.
+const ANALYSIS_CANDIDATES: &[&str] = &["claude-3-5-sonnet", "gpt-4", "gemini-pro"];
+
+/// One token-level event out of `generate_text_stream`. Tool arguments arrive
+/// fragmented across deltas; callers concatenate `arguments_json_chunk` per
+/// `id` and parse once the block completes (signalled by the next event or
+/// stream end), rather than this layer trying to parse partial JSON itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum StreamEvent {
+    TextDelta(String),
+    ToolCallDelta {
+        id: String,
+        name: Option<String>,
+        arguments_json_chunk: String,
+    },
+    Usage(AIUsage),
+}
+
+/// Incremental parser state for a single SSE connection: the leftover partial
+/// line buffer plus the in-flight tool-call id/name a provider announced in an
+/// opening event before streaming its argument fragments.
+struct SseState<S> {
+    byte_stream: S,
+    buffer: String,
+    current_tool_id: Option<String>,
+    current_tool_name: Option<String>,
+    model: AIModel,
+    usage: Arc<UsageTracker>,
+}
+
+/// Pulls the next complete `data: ...` payload out of an SSE buffer, if any,
+/// returning it along with the remaining unconsumed buffer. Shared by every
+/// provider so only this function needs to know about SSE line framing.
+fn next_sse_payload(buffer: &str) -> Option<(String, String)> {
+    let event_end = buffer.find("\n\n")?;
+    let (event, rest) = buffer.split_at(event_end);
+    let rest = rest[2..].to_string();
+
+    let data: String = event
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(|line| line.trim_start())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Some((data, rest))
+}
+
+/// Merges `AIRequest.parameters` into a provider payload so callers can pass
+/// through temperature, top_p, stop sequences, or override the default
+/// `max_tokens` without this layer needing to know about each option.
+/// User-supplied keys win over whatever the payload already set.
+fn merge_request_parameters(payload: &mut serde_json::Value, parameters: &HashMap<String, serde_json::Value>) {
+    if let serde_json::Value::Object(map) = payload {
+        for (key, value) in parameters {
+            map.insert(key.clone(), value.clone());
+        }
+    }
+}
+
+/// Carries the HTTP status from a non-2xx provider response so
+/// `generate_with_routing` can tell a transient failure (429/5xx) from a
+/// permanent one (4xx) without re-parsing the error message.
+#[derive(Debug)]
+struct ProviderApiError {
+    status: reqwest::StatusCode,
+    body: String,
+}
+
+impl std::fmt::Display for ProviderApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "provider API error {}: {}", self.status, self.body)
+    }
+}
+
+impl std::error::Error for ProviderApiError {}
+
+impl ProviderApiError {
+    fn is_retryable(&self) -> bool {
+        self.status == reqwest::StatusCode::TOO_MANY_REQUESTS || self.status.is_server_error()
+    }
+}
+
+/// Whether `generate_with_routing` should retry the same candidate after
+/// this error: true for rate limits, server errors, and network failures,
+/// false for anything that a retry can't fix (bad request, missing model,
+/// auth failure). Either way, a candidate that fails moves routing on to
+/// the next one.
+fn is_retryable_error(err: &anyhow::Error) -> bool {
+    if let Some(api_err) = err.downcast_ref::<ProviderApiError>() {
+        return api_err.is_retryable();
+    }
+    err.downcast_ref::<reqwest::Error>().is_some()
+}
+
+/// Governs how many times `generate_with_routing` retries a single candidate
+/// model, with exponential backoff, before moving on to the next one.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: std::time::Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self { max_attempts: 3, base_delay: std::time::Duration::from_millis(250) }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolSpec {
+    pub name: String,
+    pub description: String,
+    pub parameters: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    pub arguments: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageRole {
+    User,
+    Assistant,
+    Tool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Message {
+    pub role: MessageRole,
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCall>,
+    #[serde(default)]
+    pub tool_call_id: Option<String>,
+}
+
+impl Message {
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: MessageRole::User,
+            content: Some(content.into()),
+            tool_calls: vec![],
+            tool_call_id: None,
+        }
+    }
+
+    pub fn assistant(content: String, tool_calls: Vec<ToolCall>) -> Self {
+        Self {
+            role: MessageRole::Assistant,
+            content: if content.is_empty() { None } else { Some(content) },
+            tool_calls,
+            tool_call_id: None,
+        }
+    }
+
+    pub fn tool_result(tool_call_id: String, result: &serde_json::Value) -> Self {
+        Self {
+            role: MessageRole::Tool,
+            content: Some(result.to_string()),
+            tool_calls: vec![],
+            tool_call_id: Some(tool_call_id),
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AIRequest {
@@ -9,6 +192,8 @@ pub struct AIRequest {
     pub prompt: String,
     pub context: Option<String>,
     pub parameters: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub tools: Vec<ToolSpec>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +213,39 @@ pub struct AIUsage {
     pub cost: Option<f64>,
 }
 
+impl AIUsage {
+    fn zero() -> Self {
+        Self { prompt_tokens: 0, completion_tokens: 0, total_tokens: 0, cost: Some(0.0) }
+    }
+
+    fn accumulate(&mut self, other: &AIUsage) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.total_tokens += other.total_tokens;
+        self.cost = Some(self.cost.unwrap_or(0.0) + other.cost.unwrap_or(0.0));
+    }
+}
+
+/// Accumulates token and cost totals per model and per provider across the
+/// process lifetime, keyed `model:<name>` / `provider:<name>`, so operators
+/// can chart real spend instead of only per-call estimates.
+#[derive(Default)]
+pub struct UsageTracker {
+    totals: RwLock<HashMap<String, AIUsage>>,
+}
+
+impl UsageTracker {
+    pub(crate) async fn record(&self, model: &str, provider: &str, usage: &AIUsage) {
+        let mut totals = self.totals.write().await;
+        totals.entry(format!("model:{}", model)).or_insert_with(AIUsage::zero).accumulate(usage);
+        totals.entry(format!("provider:{}", provider)).or_insert_with(AIUsage::zero).accumulate(usage);
+    }
+
+    pub(crate) async fn summary(&self) -> HashMap<String, AIUsage> {
+        self.totals.read().await.clone()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AIModel {
     pub id: String,
@@ -36,143 +254,689 @@ pub struct AIModel {
     pub capabilities: Vec<String>,
     pub max_tokens: u32,
     pub cost_per_token: Option<f64>,
+    /// Per-token input/output rates, since providers price them differently
+    /// (Anthropic and OpenAI both charge several times more for output than
+    /// input). Falls back to `cost_per_token` for whichever side is unset.
+    #[serde(default)]
+    pub input_cost_per_token: Option<f64>,
+    #[serde(default)]
+    pub output_cost_per_token: Option<f64>,
+}
+
+impl AIModel {
+    fn supports(&self, capability: &str) -> bool {
+        self.capabilities.iter().any(|c| c == capability)
+    }
+
+    /// Real spend for a completion, or `None` if neither a split nor a flat
+    /// rate is configured for this model.
+    fn cost(&self, prompt_tokens: u32, completion_tokens: u32) -> Option<f64> {
+        let input_rate = self.input_cost_per_token.or(self.cost_per_token)?;
+        let output_rate = self.output_cost_per_token.or(self.cost_per_token)?;
+        Some(input_rate * prompt_tokens as f64 + output_rate * completion_tokens as f64)
+    }
+}
+
+/// A single entry in the flat `v2` model registry config — what a user edits
+/// by hand to add a newly released model without recompiling.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelConfigEntry {
+    pub provider: String,
+    pub name: String,
+    pub max_tokens: u32,
+    #[serde(default)]
+    pub cost_per_token: Option<f64>,
+    #[serde(default)]
+    pub input_cost_per_token: Option<f64>,
+    #[serde(default)]
+    pub output_cost_per_token: Option<f64>,
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+}
+
+/// Per-provider connection details, replacing the old parallel
+/// `api_keys`/`api_endpoint` maps with one entry per provider.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderEntry {
+    pub base_url: String,
+    #[serde(default)]
+    pub api_key: Option<String>,
+}
+
+/// The flat, versioned registry config loaded at `AIIntegration` construction.
+/// `version: 1` configs use the old nested `{ ai_models: [{ model_type, api_endpoint, ... }] }`
+/// shape from `PowerAutomationConfig`; `from_value()` upgrades them in-memory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelRegistryConfig {
+    pub version: u32,
+    #[serde(default)]
+    pub available_models: Vec<ModelConfigEntry>,
+    #[serde(default)]
+    pub providers: HashMap<String, ProviderEntry>,
+}
+
+impl ModelRegistryConfig {
+    /// Loads a registry config from raw JSON, transparently upgrading a v1
+    /// nested config (or the legacy `PowerAutomationConfig.ai_models` shape)
+    /// to the current flat v2 format so existing users don't break.
+    pub fn from_value(raw: serde_json::Value) -> Result<Self> {
+        let version = raw.get("version").and_then(|v| v.as_u64()).unwrap_or(1);
+        if version >= 2 {
+            return serde_json::from_value(raw)
+                .map_err(|e| anyhow!("Failed to parse v{} model registry config: {}", version, e));
+        }
+
+        #[derive(Deserialize)]
+        struct LegacyModelConfig {
+            name: String,
+            model_type: String,
+            api_endpoint: String,
+            #[serde(default)]
+            api_key: Option<String>,
+            #[serde(default)]
+            capabilities: Vec<String>,
+        }
+        #[derive(Deserialize)]
+        struct LegacyConfig {
+            #[serde(default)]
+            ai_models: Vec<LegacyModelConfig>,
+        }
+
+        let legacy: LegacyConfig = serde_json::from_value(raw)
+            .map_err(|e| anyhow!("Failed to parse legacy (v1) model registry config: {}", e))?;
+
+        let mut providers = HashMap::new();
+        let mut available_models = Vec::new();
+        for model in legacy.ai_models {
+            providers.entry(model.model_type.clone()).or_insert_with(|| ProviderEntry {
+                base_url: model.api_endpoint.clone(),
+                api_key: model.api_key.clone(),
+            });
+            available_models.push(ModelConfigEntry {
+                provider: model.model_type,
+                name: model.name,
+                max_tokens: 4096,
+                cost_per_token: None,
+                input_cost_per_token: None,
+                output_cost_per_token: None,
+                capabilities: model.capabilities,
+            });
+        }
+
+        info!(models = available_models.len(), "migrated v1 model registry config to v2");
+        Ok(Self { version: 2, available_models, providers })
+    }
+
+    /// Built-in defaults, merged under whatever a user config supplies.
+    pub fn builtin_defaults() -> Self {
+        Self {
+            version: 2,
+            providers: HashMap::from([
+                ("anthropic".to_string(), ProviderEntry {
+                    base_url: "https://api.anthropic.com/v1/messages".to_string(),
+                    api_key: None,
+                }),
+                ("openai".to_string(), ProviderEntry {
+                    base_url: "https://api.openai.com/v1/chat/completions".to_string(),
+                    api_key: None,
+                }),
+                ("google".to_string(), ProviderEntry {
+                    base_url: "https://generativelanguage.googleapis.com/v1".to_string(),
+                    api_key: None,
+                }),
+            ]),
+            available_models: vec![
+                ModelConfigEntry {
+                    provider: "anthropic".to_string(),
+                    name: "claude-3-5-sonnet".to_string(),
+                    max_tokens: 200000,
+                    cost_per_token: Some(0.000003),
+                    input_cost_per_token: Some(0.000003),
+                    output_cost_per_token: Some(0.000015),
+                    capabilities: vec![
+                        "text_generation".to_string(),
+                        "code_generation".to_string(),
+                        "analysis".to_string(),
+                        "reasoning".to_string(),
+                        "function_calling".to_string(),
+                    ],
+                },
+                ModelConfigEntry {
+                    provider: "anthropic".to_string(),
+                    name: "claude-3-haiku".to_string(),
+                    max_tokens: 200000,
+                    cost_per_token: Some(0.00000025),
+                    input_cost_per_token: Some(0.00000025),
+                    output_cost_per_token: Some(0.00000125),
+                    capabilities: vec![
+                        "text_generation".to_string(),
+                        "code_generation".to_string(),
+                        "fast_response".to_string(),
+                    ],
+                },
+                ModelConfigEntry {
+                    provider: "openai".to_string(),
+                    name: "gpt-4".to_string(),
+                    max_tokens: 128000,
+                    cost_per_token: Some(0.00003),
+                    input_cost_per_token: Some(0.00003),
+                    output_cost_per_token: Some(0.00006),
+                    capabilities: vec![
+                        "text_generation".to_string(),
+                        "code_generation".to_string(),
+                        "function_calling".to_string(),
+                        "vision".to_string(),
+                    ],
+                },
+                ModelConfigEntry {
+                    provider: "openai".to_string(),
+                    name: "gpt-3.5-turbo".to_string(),
+                    max_tokens: 16385,
+                    cost_per_token: Some(0.0000015),
+                    input_cost_per_token: Some(0.0000015),
+                    output_cost_per_token: Some(0.000002),
+                    capabilities: vec![
+                        "text_generation".to_string(),
+                        "code_generation".to_string(),
+                        "function_calling".to_string(),
+                    ],
+                },
+                ModelConfigEntry {
+                    provider: "google".to_string(),
+                    name: "gemini-pro".to_string(),
+                    max_tokens: 32768,
+                    cost_per_token: Some(0.000001),
+                    input_cost_per_token: Some(0.000001),
+                    output_cost_per_token: Some(0.000002),
+                    capabilities: vec![
+                        "text_generation".to_string(),
+                        "code_generation".to_string(),
+                        "multimodal".to_string(),
+                    ],
+                },
+            ],
+        }
+    }
+}
+
+/// One turn of a provider conversation: either a final text answer, or a
+/// request to run one or more tools before the model can continue.
+struct ProviderTurn {
+    content: String,
+    tool_calls: Vec<ToolCall>,
+    usage: Option<AIUsage>,
 }
 
 pub struct AIIntegration {
     models: HashMap<String, AIModel>,
-    api_keys: HashMap<String, String>,
+    providers: HashMap<String, ProviderEntry>,
+    tool_core: Option<Arc<PowerAutomationCore>>,
+    usage: Arc<UsageTracker>,
 }
 
 impl AIIntegration {
-    pub fn new() -> Self {
+    /// Builds the registry from `config`, merging its models and providers
+    /// over the built-in defaults so a user config only needs to list what it
+    /// wants to add or override.
+    pub fn new(config: ModelRegistryConfig) -> Self {
         let mut integration = Self {
             models: HashMap::new(),
-            api_keys: HashMap::new(),
+            providers: HashMap::new(),
+            tool_core: None,
+            usage: Arc::new(UsageTracker::default()),
         };
-        
-        integration.initialize_models();
+
+        integration.apply_config(Self::default_config());
+        integration.apply_config(config);
         integration
     }
-    
-    fn initialize_models(&mut self) {
-        // Claude Models
-        self.models.insert("claude-3-5-sonnet".to_string(), AIModel {
-            id: "claude-3-5-sonnet".to_string(),
-            name: "Claude 3.5 Sonnet".to_string(),
-            provider: "anthropic".to_string(),
-            capabilities: vec![
-                "text_generation".to_string(),
-                "code_generation".to_string(),
-                "analysis".to_string(),
-                "reasoning".to_string(),
-            ],
-            max_tokens: 200000,
-            cost_per_token: Some(0.000003),
-        });
-        
-        self.models.insert("claude-3-haiku".to_string(), AIModel {
-            id: "claude-3-haiku".to_string(),
-            name: "Claude 3 Haiku".to_string(),
-            provider: "anthropic".to_string(),
-            capabilities: vec![
-                "text_generation".to_string(),
-                "code_generation".to_string(),
-                "fast_response".to_string(),
-            ],
-            max_tokens: 200000,
-            cost_per_token: Some(0.00000025),
-        });
-        
-        // OpenAI Models
-        self.models.insert("gpt-4".to_string(), AIModel {
-            id: "gpt-4".to_string(),
-            name: "GPT-4".to_string(),
-            provider: "openai".to_string(),
-            capabilities: vec![
-                "text_generation".to_string(),
-                "code_generation".to_string(),
-                "function_calling".to_string(),
-                "vision".to_string(),
-            ],
-            max_tokens: 128000,
-            cost_per_token: Some(0.00003),
-        });
-        
-        self.models.insert("gpt-3.5-turbo".to_string(), AIModel {
-            id: "gpt-3.5-turbo".to_string(),
-            name: "GPT-3.5 Turbo".to_string(),
-            provider: "openai".to_string(),
-            capabilities: vec![
-                "text_generation".to_string(),
-                "code_generation".to_string(),
-                "function_calling".to_string(),
-            ],
-            max_tokens: 16385,
-            cost_per_token: Some(0.0000015),
-        });
-        
-        // Google Models
-        self.models.insert("gemini-pro".to_string(), AIModel {
-            id: "gemini-pro".to_string(),
-            name: "Gemini Pro".to_string(),
-            provider: "google".to_string(),
-            capabilities: vec![
-                "text_generation".to_string(),
-                "code_generation".to_string(),
-                "multimodal".to_string(),
-            ],
-            max_tokens: 32768,
-            cost_per_token: Some(0.000001),
-        });
+
+    fn default_config() -> ModelRegistryConfig {
+        ModelRegistryConfig::builtin_defaults()
     }
-    
+
+    fn apply_config(&mut self, config: ModelRegistryConfig) {
+        for (provider, entry) in config.providers {
+            self.providers.insert(provider, entry);
+        }
+        for entry in config.available_models {
+            self.models.insert(entry.name.clone(), AIModel {
+                id: entry.name.clone(),
+                name: entry.name,
+                provider: entry.provider,
+                capabilities: entry.capabilities,
+                max_tokens: entry.max_tokens,
+                cost_per_token: entry.cost_per_token,
+                input_cost_per_token: entry.input_cost_per_token,
+                output_cost_per_token: entry.output_cost_per_token,
+            });
+        }
+    }
+
+    /// Applies a fresh registry config over the current one without
+    /// reconstructing the whole integration, so a settings change (e.g. a
+    /// newly released model) takes effect immediately. Also propagates the
+    /// same config to `tool_core` (when wired via `set_tool_core`), so its
+    /// independent `AIModelConfig` copy doesn't drift back out of sync the
+    /// next time either side reloads.
+    pub async fn reload_config(&mut self, config: ModelRegistryConfig) -> Result<()> {
+        info!(version = config.version, "reloading AI model registry");
+
+        if let Some(core) = &self.tool_core {
+            core.reload_config(&config).await?;
+        }
+        self.apply_config(config);
+        Ok(())
+    }
+
+    /// Wires this integration to the tool registry that backs function calling.
+    /// Without this, a request carrying `tools` will fail as soon as the model
+    /// tries to invoke one.
+    pub fn set_tool_core(&mut self, core: Arc<PowerAutomationCore>) {
+        self.tool_core = Some(core);
+    }
+
     pub fn get_available_models(&self) -> Vec<&AIModel> {
         self.models.values().collect()
     }
-    
+
     pub fn get_model(&self, model_id: &str) -> Option<&AIModel> {
         self.models.get(model_id)
     }
-    
+
     pub fn set_api_key(&mut self, provider: &str, api_key: String) {
-        self.api_keys.insert(provider.to_string(), api_key);
+        self.providers.entry(provider.to_string())
+            .or_insert_with(|| ProviderEntry { base_url: String::new(), api_key: None })
+            .api_key = Some(api_key);
     }
-    
+
+    fn provider_entry(&self, provider: &str) -> Result<&ProviderEntry> {
+        self.providers.get(provider)
+            .ok_or_else(|| anyhow!("Provider '{}' is not configured", provider))
+    }
+
+    #[instrument(skip(self, request), fields(request_id = %request.id, model = %request.model, provider))]
     pub async fn generate_text(&self, request: AIRequest) -> Result<AIResponse> {
-        log::info!("Generating text with model: {}", request.model);
-        
         let model = self.models.get(&request.model)
             .ok_or_else(|| anyhow!("Model '{}' not found", request.model))?;
-        
-        let api_key = self.api_keys.get(&model.provider)
+        tracing::Span::current().record("provider", model.provider.as_str());
+        info!("generating text");
+
+        // Fail fast if the provider has no key, rather than discovering it
+        // several tool-calling steps into the loop below.
+        self.provider_entry(&model.provider)?.api_key.as_ref()
+            .ok_or_else(|| anyhow!("API key not found for provider '{}'", model.provider))?;
+
+        if !request.tools.is_empty() && !model.supports("function_calling") {
+            return Err(anyhow!(
+                "Model '{}' does not support function_calling but {} tools were supplied",
+                request.model,
+                request.tools.len()
+            ));
+        }
+
+        let mut messages = vec![Message::user(request.prompt.clone())];
+        // Keyed by (tool name, arguments json) so an identical call within this
+        // conversation is served from cache instead of re-dispatched.
+        let mut tool_cache: HashMap<(String, String), serde_json::Value> = HashMap::new();
+
+        for _ in 0..MAX_TOOL_CALL_STEPS {
+            let turn = match model.provider.as_str() {
+                "anthropic" => self.call_anthropic_api(&request, model, &messages).await?,
+                "openai" => self.call_openai_api(&request, model, &messages).await?,
+                "google" => self.call_google_turn(&request).await?,
+                _ => return Err(anyhow!("Unsupported provider: {}", model.provider)),
+            };
+
+            if let Some(usage) = &turn.usage {
+                self.usage.record(&model.name, &model.provider, usage).await;
+            }
+
+            if turn.tool_calls.is_empty() {
+                return Ok(AIResponse {
+                    id: request.id.clone(),
+                    model: request.model.clone(),
+                    content: turn.content,
+                    usage: turn.usage,
+                    metadata: HashMap::new(),
+                });
+            }
+
+            messages.push(Message::assistant(turn.content, turn.tool_calls.clone()));
+
+            let core = self.tool_core.as_ref()
+                .ok_or_else(|| anyhow!("no tool core configured; cannot dispatch tool calls"))?;
+
+            for call in &turn.tool_calls {
+                let cache_key = (call.name.clone(), call.arguments.to_string());
+                let result = if let Some(cached) = tool_cache.get(&cache_key) {
+                    cached.clone()
+                } else {
+                    let result = core.invoke_tool(&call.name, call.arguments.clone()).await?;
+                    tool_cache.insert(cache_key, result.clone());
+                    result
+                };
+                messages.push(Message::tool_result(call.id.clone(), &result));
+            }
+        }
+
+        error!(steps = MAX_TOOL_CALL_STEPS, "tool-calling loop exceeded max steps without a final answer");
+        Err(anyhow!(
+            "tool-calling loop for request '{}' exceeded {} steps without a final answer",
+            request.id, MAX_TOOL_CALL_STEPS
+        ))
+    }
+
+    /// Streaming counterpart of `generate_text`. Unlike the buffered path this
+    /// does not drive the tool-calling loop itself — it yields deltas for a
+    /// single turn so the editor UI can render tokens as they arrive; a caller
+    /// that needs the full multi-step loop should keep using `generate_text`.
+    #[instrument(skip(self, request), fields(request_id = %request.id, model = %request.model, provider))]
+    pub async fn generate_text_stream(&self, request: AIRequest) -> Result<BoxStream<'static, Result<StreamEvent>>> {
+        let model = self.models.get(&request.model)
+            .ok_or_else(|| anyhow!("Model '{}' not found", request.model))?;
+        tracing::Span::current().record("provider", model.provider.as_str());
+        info!("streaming text");
+
+        let provider = self.provider_entry(&model.provider)?;
+        let api_key = provider.api_key.clone()
             .ok_or_else(|| anyhow!("API key not found for provider '{}'", model.provider))?;
-        
+        let base_url = provider.base_url.clone();
+
+        if !request.tools.is_empty() && !model.supports("function_calling") {
+            return Err(anyhow!(
+                "Model '{}' does not support function_calling but {} tools were supplied",
+                request.model,
+                request.tools.len()
+            ));
+        }
+
+        let model = model.clone();
+        let usage = self.usage.clone();
         match model.provider.as_str() {
-            "anthropic" => self.call_anthropic_api(&request, api_key).await,
-            "openai" => self.call_openai_api(&request, api_key).await,
-            "google" => self.call_google_api(&request, api_key).await,
-            _ => Err(anyhow!("Unsupported provider: {}", model.provider)),
+            "anthropic" => Self::stream_anthropic(request, base_url, api_key, model, usage).await,
+            "openai" => Self::stream_openai(request, base_url, api_key, model, usage).await,
+            _ => Err(anyhow!("Streaming is not supported for provider: {}", model.provider)),
+        }
+    }
+
+    async fn stream_anthropic(request: AIRequest, base_url: String, api_key: String, model: AIModel, usage: Arc<UsageTracker>) -> Result<BoxStream<'static, Result<StreamEvent>>> {
+        let client = reqwest::Client::new();
+
+        let mut payload = serde_json::json!({
+            "model": request.model,
+            "max_tokens": 4096,
+            "stream": true,
+            "messages": [{"role": "user", "content": request.prompt}],
+        });
+        if !request.tools.is_empty() {
+            let tools: Vec<serde_json::Value> = request.tools.iter().map(|t| serde_json::json!({
+                "name": t.name,
+                "description": t.description,
+                "input_schema": t.parameters,
+            })).collect();
+            payload["tools"] = serde_json::Value::Array(tools);
+        }
+        merge_request_parameters(&mut payload, &request.parameters);
+
+        let response = client
+            .post(&base_url)
+            .header("Content-Type", "application/json")
+            .header("x-api-key", api_key)
+            .header("anthropic-version", "2023-06-01")
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to call Anthropic API: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            error!(%status, body = %error_text, "Anthropic API returned an error");
+            return Err(anyhow!(ProviderApiError { status, body: error_text }));
         }
+
+        let state = SseState {
+            byte_stream: response.bytes_stream(),
+            buffer: String::new(),
+            current_tool_id: None,
+            current_tool_name: None,
+            model,
+            usage,
+        };
+
+        let stream = futures_util::stream::try_unfold(state, |mut state| async move {
+            loop {
+                if let Some((data, rest)) = next_sse_payload(&state.buffer) {
+                    state.buffer = rest;
+                    if data.is_empty() || data == "[DONE]" {
+                        continue;
+                    }
+                    let event: serde_json::Value = match serde_json::from_str(&data) {
+                        Ok(v) => v,
+                        Err(_) => continue,
+                    };
+                    match event["type"].as_str() {
+                        Some("content_block_start") => {
+                            if event["content_block"]["type"].as_str() == Some("tool_use") {
+                                state.current_tool_id = event["content_block"]["id"].as_str().map(String::from);
+                                state.current_tool_name = event["content_block"]["name"].as_str().map(String::from);
+                            }
+                        }
+                        Some("content_block_delta") => {
+                            match event["delta"]["type"].as_str() {
+                                Some("text_delta") => {
+                                    let text = event["delta"]["text"].as_str().unwrap_or("").to_string();
+                                    return Ok(Some((StreamEvent::TextDelta(text), state)));
+                                }
+                                Some("input_json_delta") => {
+                                    let chunk = event["delta"]["partial_json"].as_str().unwrap_or("").to_string();
+                                    let id = state.current_tool_id.clone().unwrap_or_default();
+                                    let name = state.current_tool_name.take();
+                                    return Ok(Some((StreamEvent::ToolCallDelta { id, name, arguments_json_chunk: chunk }, state)));
+                                }
+                                _ => {}
+                            }
+                        }
+                        Some("message_delta") => {
+                            if let Some(usage_data) = event.get("usage") {
+                                let prompt_tokens = usage_data["input_tokens"].as_u64().unwrap_or(0) as u32;
+                                let completion_tokens = usage_data["output_tokens"].as_u64().unwrap_or(0) as u32;
+                                let cost = state.model.cost(prompt_tokens, completion_tokens);
+                                let usage = AIUsage {
+                                    prompt_tokens,
+                                    completion_tokens,
+                                    total_tokens: prompt_tokens + completion_tokens,
+                                    cost,
+                                };
+                                state.usage.record(&state.model.name, &state.model.provider, &usage).await;
+                                return Ok(Some((StreamEvent::Usage(usage), state)));
+                            }
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                match state.byte_stream.next().await {
+                    Some(Ok(bytes)) => state.buffer.push_str(&String::from_utf8_lossy(&bytes)),
+                    Some(Err(e)) => return Err(anyhow!("Anthropic stream error: {}", e)),
+                    None => return Ok(None),
+                }
+            }
+        });
+
+        Ok(stream.boxed())
     }
-    
-    async fn call_anthropic_api(&self, request: &AIRequest, api_key: &str) -> Result<AIResponse> {
-        log::info!("Calling Anthropic API for model: {}", request.model);
-        
+
+    async fn stream_openai(request: AIRequest, base_url: String, api_key: String, model: AIModel, usage: Arc<UsageTracker>) -> Result<BoxStream<'static, Result<StreamEvent>>> {
         let client = reqwest::Client::new();
-        
-        let payload = serde_json::json!({
+
+        let mut payload = serde_json::json!({
             "model": request.model,
+            "stream": true,
+            "messages": [{"role": "user", "content": request.prompt}],
             "max_tokens": 4096,
-            "messages": [
-                {
+        });
+        if !request.tools.is_empty() {
+            let functions: Vec<serde_json::Value> = request.tools.iter().map(|t| serde_json::json!({
+                "type": "function",
+                "function": {"name": t.name, "description": t.description, "parameters": t.parameters},
+            })).collect();
+            payload["tools"] = serde_json::Value::Array(functions);
+        }
+        merge_request_parameters(&mut payload, &request.parameters);
+
+        let response = client
+            .post(&base_url)
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Bearer {}", api_key))
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to call OpenAI API: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            error!(%status, body = %error_text, "OpenAI API returned an error");
+            return Err(anyhow!(ProviderApiError { status, body: error_text }));
+        }
+
+        let state = SseState {
+            byte_stream: response.bytes_stream(),
+            buffer: String::new(),
+            current_tool_id: None,
+            current_tool_name: None,
+            model,
+            usage,
+        };
+
+        let stream = futures_util::stream::try_unfold(state, |mut state| async move {
+            loop {
+                if let Some((data, rest)) = next_sse_payload(&state.buffer) {
+                    state.buffer = rest;
+                    if data.is_empty() || data == "[DONE]" {
+                        continue;
+                    }
+                    let event: serde_json::Value = match serde_json::from_str(&data) {
+                        Ok(v) => v,
+                        Err(_) => continue,
+                    };
+                    let delta = &event["choices"][0]["delta"];
+                    if let Some(text) = delta["content"].as_str() {
+                        if !text.is_empty() {
+                            return Ok(Some((StreamEvent::TextDelta(text.to_string()), state)));
+                        }
+                    }
+                    if let Some(calls) = delta["tool_calls"].as_array() {
+                        if let Some(call) = calls.first() {
+                            let id = call["id"].as_str().map(String::from)
+                                .unwrap_or_else(|| state.current_tool_id.clone().unwrap_or_default());
+                            state.current_tool_id = Some(id.clone());
+                            let name = call["function"]["name"].as_str().map(String::from);
+                            let chunk = call["function"]["arguments"].as_str().unwrap_or("").to_string();
+                            return Ok(Some((StreamEvent::ToolCallDelta { id, name, arguments_json_chunk: chunk }, state)));
+                        }
+                    }
+                    if let Some(usage_data) = event.get("usage") {
+                        if !usage_data.is_null() {
+                            let prompt_tokens = usage_data["prompt_tokens"].as_u64().unwrap_or(0) as u32;
+                            let completion_tokens = usage_data["completion_tokens"].as_u64().unwrap_or(0) as u32;
+                            let cost = state.model.cost(prompt_tokens, completion_tokens);
+                            let usage = AIUsage {
+                                prompt_tokens,
+                                completion_tokens,
+                                total_tokens: usage_data["total_tokens"].as_u64().unwrap_or(0) as u32,
+                                cost,
+                            };
+                            state.usage.record(&state.model.name, &state.model.provider, &usage).await;
+                            return Ok(Some((StreamEvent::Usage(usage), state)));
+                        }
+                    }
+                    continue;
+                }
+
+                match state.byte_stream.next().await {
+                    Some(Ok(bytes)) => state.buffer.push_str(&String::from_utf8_lossy(&bytes)),
+                    Some(Err(e)) => return Err(anyhow!("OpenAI stream error: {}", e)),
+                    None => return Ok(None),
+                }
+            }
+        });
+
+        Ok(stream.boxed())
+    }
+
+    #[instrument(skip(self, request, messages), fields(request_id = %request.id, model = %request.model, provider = "anthropic"))]
+    async fn call_anthropic_api(
+        &self,
+        request: &AIRequest,
+        model: &AIModel,
+        messages: &[Message],
+    ) -> Result<ProviderTurn> {
+        let provider = self.provider_entry(&model.provider)?;
+        let api_key = provider.api_key.as_ref()
+            .ok_or_else(|| anyhow!("API key not found for provider '{}'", model.provider))?;
+
+        let client = reqwest::Client::new();
+
+        let anthropic_messages: Vec<serde_json::Value> = messages.iter().map(|m| {
+            match m.role {
+                MessageRole::User => serde_json::json!({
                     "role": "user",
-                    "content": request.prompt
+                    "content": m.content.clone().unwrap_or_default(),
+                }),
+                MessageRole::Assistant if m.tool_calls.is_empty() => serde_json::json!({
+                    "role": "assistant",
+                    "content": m.content.clone().unwrap_or_default(),
+                }),
+                MessageRole::Assistant => {
+                    let mut blocks: Vec<serde_json::Value> = Vec::new();
+                    if let Some(text) = &m.content {
+                        if !text.is_empty() {
+                            blocks.push(serde_json::json!({"type": "text", "text": text}));
+                        }
+                    }
+                    for call in &m.tool_calls {
+                        blocks.push(serde_json::json!({
+                            "type": "tool_use",
+                            "id": call.id,
+                            "name": call.name,
+                            "input": call.arguments,
+                        }));
+                    }
+                    serde_json::json!({"role": "assistant", "content": blocks})
                 }
-            ]
+                MessageRole::Tool => serde_json::json!({
+                    "role": "user",
+                    "content": [{
+                        "type": "tool_result",
+                        "tool_use_id": m.tool_call_id.clone().unwrap_or_default(),
+                        "content": m.content.clone().unwrap_or_default(),
+                    }]
+                }),
+            }
+        }).collect();
+
+        let mut payload = serde_json::json!({
+            "model": request.model,
+            "max_tokens": 4096,
+            "messages": anthropic_messages,
         });
-        
+
+        if !request.tools.is_empty() {
+            let tools: Vec<serde_json::Value> = request.tools.iter().map(|t| serde_json::json!({
+                "name": t.name,
+                "description": t.description,
+                "input_schema": t.parameters,
+            })).collect();
+            payload["tools"] = serde_json::Value::Array(tools);
+        }
+        merge_request_parameters(&mut payload, &request.parameters);
+
         let response = client
-            .post("https://api.anthropic.com/v1/messages")
+            .post(&provider.base_url)
             .header("Content-Type", "application/json")
             .header("x-api-key", api_key)
             .header("anthropic-version", "2023-06-01")
@@ -180,142 +944,270 @@ impl AIIntegration {
             .send()
             .await
             .map_err(|e| anyhow!("Failed to call Anthropic API: {}", e))?;
-        
+
         if !response.status().is_success() {
+            let status = response.status();
             let error_text = response.text().await.unwrap_or_default();
-            return Err(anyhow!("Anthropic API error: {}", error_text));
+            error!(%status, body = %error_text, "Anthropic API returned an error");
+            return Err(anyhow!(ProviderApiError { status, body: error_text }));
         }
-        
+
         let response_data: serde_json::Value = response.json().await
             .map_err(|e| anyhow!("Failed to parse Anthropic response: {}", e))?;
-        
-        let content = response_data["content"][0]["text"]
-            .as_str()
-            .unwrap_or("")
-            .to_string();
-        
+
+        let mut content = String::new();
+        let mut tool_calls = Vec::new();
+        if let Some(blocks) = response_data["content"].as_array() {
+            for block in blocks {
+                match block["type"].as_str() {
+                    Some("text") => content.push_str(block["text"].as_str().unwrap_or("")),
+                    Some("tool_use") => tool_calls.push(ToolCall {
+                        id: block["id"].as_str().unwrap_or_default().to_string(),
+                        name: block["name"].as_str().unwrap_or_default().to_string(),
+                        arguments: block["input"].clone(),
+                    }),
+                    _ => {}
+                }
+            }
+        }
+
         let usage = if let Some(usage_data) = response_data.get("usage") {
+            let prompt_tokens = usage_data["input_tokens"].as_u64().unwrap_or(0) as u32;
+            let completion_tokens = usage_data["output_tokens"].as_u64().unwrap_or(0) as u32;
             Some(AIUsage {
-                prompt_tokens: usage_data["input_tokens"].as_u64().unwrap_or(0) as u32,
-                completion_tokens: usage_data["output_tokens"].as_u64().unwrap_or(0) as u32,
-                total_tokens: (usage_data["input_tokens"].as_u64().unwrap_or(0) + 
-                              usage_data["output_tokens"].as_u64().unwrap_or(0)) as u32,
-                cost: None, // Calculate based on model pricing
+                prompt_tokens,
+                completion_tokens,
+                total_tokens: prompt_tokens + completion_tokens,
+                cost: model.cost(prompt_tokens, completion_tokens),
             })
         } else {
             None
         };
-        
-        Ok(AIResponse {
-            id: request.id.clone(),
-            model: request.model.clone(),
-            content,
-            usage,
-            metadata: HashMap::new(),
-        })
+
+        Ok(ProviderTurn { content, tool_calls, usage })
     }
-    
-    async fn call_openai_api(&self, request: &AIRequest, api_key: &str) -> Result<AIResponse> {
-        log::info!("Calling OpenAI API for model: {}", request.model);
-        
+
+    #[instrument(skip(self, request, messages), fields(request_id = %request.id, model = %request.model, provider = "openai"))]
+    async fn call_openai_api(
+        &self,
+        request: &AIRequest,
+        model: &AIModel,
+        messages: &[Message],
+    ) -> Result<ProviderTurn> {
+        let provider = self.provider_entry(&model.provider)?;
+        let api_key = provider.api_key.as_ref()
+            .ok_or_else(|| anyhow!("API key not found for provider '{}'", model.provider))?;
+
         let client = reqwest::Client::new();
-        
-        let payload = serde_json::json!({
-            "model": request.model,
-            "messages": [
-                {
+
+        let openai_messages: Vec<serde_json::Value> = messages.iter().map(|m| {
+            match m.role {
+                MessageRole::User => serde_json::json!({
                     "role": "user",
-                    "content": request.prompt
+                    "content": m.content.clone().unwrap_or_default(),
+                }),
+                MessageRole::Assistant => {
+                    let mut msg = serde_json::json!({
+                        "role": "assistant",
+                        "content": m.content.clone(),
+                    });
+                    if !m.tool_calls.is_empty() {
+                        msg["tool_calls"] = serde_json::Value::Array(m.tool_calls.iter().map(|call| {
+                            serde_json::json!({
+                                "id": call.id,
+                                "type": "function",
+                                "function": {
+                                    "name": call.name,
+                                    "arguments": call.arguments.to_string(),
+                                }
+                            })
+                        }).collect());
+                    }
+                    msg
                 }
-            ],
+                MessageRole::Tool => serde_json::json!({
+                    "role": "tool",
+                    "tool_call_id": m.tool_call_id.clone().unwrap_or_default(),
+                    "content": m.content.clone().unwrap_or_default(),
+                }),
+            }
+        }).collect();
+
+        let mut payload = serde_json::json!({
+            "model": request.model,
+            "messages": openai_messages,
             "max_tokens": 4096
         });
-        
+
+        if !request.tools.is_empty() {
+            let functions: Vec<serde_json::Value> = request.tools.iter().map(|t| serde_json::json!({
+                "type": "function",
+                "function": {
+                    "name": t.name,
+                    "description": t.description,
+                    "parameters": t.parameters,
+                }
+            })).collect();
+            payload["tools"] = serde_json::Value::Array(functions);
+        }
+        merge_request_parameters(&mut payload, &request.parameters);
+
         let response = client
-            .post("https://api.openai.com/v1/chat/completions")
+            .post(&provider.base_url)
             .header("Content-Type", "application/json")
             .header("Authorization", format!("Bearer {}", api_key))
             .json(&payload)
             .send()
             .await
             .map_err(|e| anyhow!("Failed to call OpenAI API: {}", e))?;
-        
+
         if !response.status().is_success() {
+            let status = response.status();
             let error_text = response.text().await.unwrap_or_default();
-            return Err(anyhow!("OpenAI API error: {}", error_text));
+            error!(%status, body = %error_text, "OpenAI API returned an error");
+            return Err(anyhow!(ProviderApiError { status, body: error_text }));
         }
-        
+
         let response_data: serde_json::Value = response.json().await
             .map_err(|e| anyhow!("Failed to parse OpenAI response: {}", e))?;
-        
-        let content = response_data["choices"][0]["message"]["content"]
-            .as_str()
-            .unwrap_or("")
-            .to_string();
-        
+
+        let message = &response_data["choices"][0]["message"];
+        let content = message["content"].as_str().unwrap_or("").to_string();
+
+        let mut tool_calls = Vec::new();
+        if let Some(calls) = message["tool_calls"].as_array() {
+            for call in calls {
+                let arguments_str = call["function"]["arguments"].as_str().unwrap_or("{}");
+                let arguments = serde_json::from_str(arguments_str).unwrap_or(serde_json::json!({}));
+                tool_calls.push(ToolCall {
+                    id: call["id"].as_str().unwrap_or_default().to_string(),
+                    name: call["function"]["name"].as_str().unwrap_or_default().to_string(),
+                    arguments,
+                });
+            }
+        }
+
         let usage = if let Some(usage_data) = response_data.get("usage") {
+            let prompt_tokens = usage_data["prompt_tokens"].as_u64().unwrap_or(0) as u32;
+            let completion_tokens = usage_data["completion_tokens"].as_u64().unwrap_or(0) as u32;
             Some(AIUsage {
-                prompt_tokens: usage_data["prompt_tokens"].as_u64().unwrap_or(0) as u32,
-                completion_tokens: usage_data["completion_tokens"].as_u64().unwrap_or(0) as u32,
+                prompt_tokens,
+                completion_tokens,
                 total_tokens: usage_data["total_tokens"].as_u64().unwrap_or(0) as u32,
-                cost: None, // Calculate based on model pricing
+                cost: model.cost(prompt_tokens, completion_tokens),
             })
         } else {
             None
         };
-        
-        Ok(AIResponse {
-            id: request.id.clone(),
-            model: request.model.clone(),
-            content,
-            usage,
-            metadata: HashMap::new(),
-        })
+
+        Ok(ProviderTurn { content, tool_calls, usage })
     }
-    
-    async fn call_google_api(&self, request: &AIRequest, api_key: &str) -> Result<AIResponse> {
-        log::info!("Calling Google API for model: {}", request.model);
-        
+
+    #[instrument(skip(self, request), fields(request_id = %request.id, model = %request.model, provider = "google"))]
+    async fn call_google_turn(&self, request: &AIRequest) -> Result<ProviderTurn> {
         // Placeholder for Google Gemini API integration
-        // This would implement the actual Google AI API calls
-        
-        Ok(AIResponse {
-            id: request.id.clone(),
-            model: request.model.clone(),
+        // This would implement the actual Google AI API calls. Gemini tool-calling
+        // is not wired up yet, so this path never returns tool_calls.
+
+        Ok(ProviderTurn {
             content: format!("Google Gemini response to: {}", request.prompt),
+            tool_calls: vec![],
             usage: Some(AIUsage {
                 prompt_tokens: 100,
                 completion_tokens: 200,
                 total_tokens: 300,
                 cost: Some(0.0003),
             }),
-            metadata: HashMap::new(),
         })
     }
-    
+
+    /// Routes a request by capability instead of a fixed model: tries each of
+    /// `candidates` in order, skipping any that don't support `capability` or
+    /// whose provider has no API key, and retries a candidate per `policy`
+    /// on a retryable failure (HTTP 429/5xx or a network error). A candidate
+    /// whose retries run out, or whose failure isn't retryable, falls back
+    /// to the next one. Returns the first success, or an error
+    /// listing every attempt if every candidate is exhausted.
+    #[instrument(skip(self, request, candidates), fields(request_id = %request.id, capability = %capability))]
+    pub async fn generate_with_routing(
+        &self,
+        capability: &str,
+        candidates: &[&str],
+        mut request: AIRequest,
+        policy: RetryPolicy,
+    ) -> Result<AIResponse> {
+        let mut attempts_log = Vec::new();
+
+        for &candidate in candidates {
+            let model = match self.models.get(candidate) {
+                Some(model) if model.supports(capability) => model,
+                Some(_) => {
+                    attempts_log.push(format!("{}: does not support capability '{}'", candidate, capability));
+                    continue;
+                }
+                None => {
+                    attempts_log.push(format!("{}: model not found", candidate));
+                    continue;
+                }
+            };
+
+            if self.provider_entry(&model.provider).ok().and_then(|p| p.api_key.as_ref()).is_none() {
+                attempts_log.push(format!("{}: no API key configured for provider '{}'", candidate, model.provider));
+                continue;
+            }
+
+            request.model = candidate.to_string();
+
+            let mut delay = policy.base_delay;
+            for attempt in 1..=policy.max_attempts {
+                match self.generate_text(request.clone()).await {
+                    Ok(response) => return Ok(response),
+                    Err(err) => {
+                        let retryable = is_retryable_error(&err);
+                        info!(candidate, attempt, retryable, error = %err, "routing attempt failed");
+                        attempts_log.push(format!("{} (attempt {}/{}): {}", candidate, attempt, policy.max_attempts, err));
+                        if !retryable || attempt == policy.max_attempts {
+                            break;
+                        }
+                        tokio::time::sleep(delay).await;
+                        delay *= 2;
+                    }
+                }
+            }
+        }
+
+        Err(anyhow!(
+            "no candidate model satisfied capability '{}'; attempts: {}",
+            capability,
+            attempts_log.join("; ")
+        ))
+    }
+
     pub async fn generate_code(&self, language: &str, description: &str, model: Option<&str>) -> Result<String> {
-        let model_id = model.unwrap_or("claude-3-5-sonnet");
-        
         let prompt = format!(
             "Generate {} code for the following description:\n\n{}\n\nPlease provide clean, well-commented code with proper error handling.",
             language, description
         );
-        
+
         let request = AIRequest {
             id: uuid::Uuid::new_v4().to_string(),
-            model: model_id.to_string(),
+            model: model.unwrap_or_default().to_string(),
             prompt,
             context: None,
             parameters: HashMap::new(),
+            tools: vec![],
+        };
+
+        let candidates: Vec<&str> = match model {
+            Some(model_id) => vec![model_id],
+            None => CODE_GENERATION_CANDIDATES.to_vec(),
         };
-        
-        let response = self.generate_text(request).await?;
+
+        let response = self.generate_with_routing("code_generation", &candidates, request, RetryPolicy::default()).await?;
         Ok(response.content)
     }
-    
+
     pub async fn analyze_code(&self, code: &str, language: &str, model: Option<&str>) -> Result<String> {
-        let model_id = model.unwrap_or("claude-3-5-sonnet");
-        
         let prompt = format!(
             "Analyze the following {} code and provide feedback on:\n\
             1. Code quality and best practices\n\
@@ -325,22 +1217,26 @@ impl AIIntegration {
             Code:\n```{}\n{}\n```",
             language, language, code
         );
-        
+
         let request = AIRequest {
             id: uuid::Uuid::new_v4().to_string(),
-            model: model_id.to_string(),
+            model: model.unwrap_or_default().to_string(),
             prompt,
             context: None,
             parameters: HashMap::new(),
+            tools: vec![],
+        };
+
+        let candidates: Vec<&str> = match model {
+            Some(model_id) => vec![model_id],
+            None => ANALYSIS_CANDIDATES.to_vec(),
         };
-        
-        let response = self.generate_text(request).await?;
+
+        let response = self.generate_with_routing("analysis", &candidates, request, RetryPolicy::default()).await?;
         Ok(response.content)
     }
-    
+
     pub async fn explain_code(&self, code: &str, language: &str, model: Option<&str>) -> Result<String> {
-        let model_id = model.unwrap_or("claude-3-5-sonnet");
-        
         let prompt = format!(
             "Explain the following {} code in detail:\n\n\
             ```{}\n{}\n```\n\n\
@@ -350,25 +1246,38 @@ impl AIIntegration {
             3. Any important concepts or patterns used",
             language, language, code
         );
-        
+
         let request = AIRequest {
             id: uuid::Uuid::new_v4().to_string(),
-            model: model_id.to_string(),
+            model: model.unwrap_or_default().to_string(),
             prompt,
             context: None,
             parameters: HashMap::new(),
+            tools: vec![],
         };
-        
-        let response = self.generate_text(request).await?;
+
+        let candidates: Vec<&str> = match model {
+            Some(model_id) => vec![model_id],
+            None => ANALYSIS_CANDIDATES.to_vec(),
+        };
+
+        let response = self.generate_with_routing("analysis", &candidates, request, RetryPolicy::default()).await?;
         Ok(response.content)
     }
-    
-    pub fn calculate_cost(&self, model_id: &str, tokens: u32) -> Option<f64> {
-        if let Some(model) = self.models.get(model_id) {
-            model.cost_per_token.map(|cost| cost * tokens as f64)
-        } else {
-            None
+
+    /// Per-model/per-provider token and spend totals accumulated across every
+    /// `generate_text`/`generate_text_stream` call so far, for spend dashboards.
+    pub async fn usage_summary(&self) -> HashMap<String, AIUsage> {
+        self.usage.summary().await
+    }
+
+    /// Reports the model's real accumulated spend once it has served at
+    /// least one request; falls back to a `tokens`-based estimate at its
+    /// configured per-token rate otherwise.
+    pub async fn calculate_cost(&self, model_id: &str, tokens: u32) -> Option<f64> {
+        if let Some(actual) = self.usage.summary().await.get(&format!("model:{}", model_id)).and_then(|u| u.cost) {
+            return Some(actual);
         }
+        self.models.get(model_id).and_then(|model| model.cost_per_token.map(|cost| cost * tokens as f64))
     }
 }
-
@@ -0,0 +1,274 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+
+/// The dependency manifest `ProjectManager::add_dependency`/
+/// `remove_dependency` found for a project, resolved from its
+/// `settings.language` (the framework is not consulted).
+pub enum DependencyTarget {
+    PackageJson(PathBuf),
+    RequirementsTxt(PathBuf),
+    Pep723Script(PathBuf),
+}
+
+impl DependencyTarget {
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::PackageJson(path) => path,
+            Self::RequirementsTxt(path) => path,
+            Self::Pep723Script(path) => path,
+        }
+    }
+}
+
+/// Finds the right manifest for a project's `language`: `package.json` for
+/// JS/TS, `requirements.txt` for Python if one exists, otherwise a lone
+/// top-level `.py` file managed as a PEP 723 single-file script.
+pub fn locate(project_path: &str, language: Option<&str>) -> Result<DependencyTarget> {
+    match language {
+        Some("typescript") | Some("javascript") => {
+            let path = Path::new(project_path).join("package.json");
+            if !path.exists() {
+                return Err(anyhow!("No 'package.json' found in '{}'", project_path));
+            }
+            Ok(DependencyTarget::PackageJson(path))
+        }
+        Some("python") => {
+            let requirements = Path::new(project_path).join("requirements.txt");
+            if requirements.exists() {
+                return Ok(DependencyTarget::RequirementsTxt(requirements));
+            }
+
+            let scripts: Vec<PathBuf> = std::fs::read_dir(project_path)
+                .map_err(|e| anyhow!("Failed to read '{}': {}", project_path, e))?
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("py"))
+                .collect();
+
+            match scripts.as_slice() {
+                [script] => Ok(DependencyTarget::Pep723Script(script.clone())),
+                [] => Err(anyhow!(
+                    "No 'requirements.txt' or single-file Python script found in '{}'", project_path
+                )),
+                _ => Err(anyhow!(
+                    "Multiple Python scripts found in '{}'; add a 'requirements.txt' to disambiguate", project_path
+                )),
+            }
+        }
+        Some(other) => Err(anyhow!("No known dependency manifest for language '{}'", other)),
+        None => Err(anyhow!("Project has no configured language")),
+    }
+}
+
+pub fn add(target: &DependencyTarget, name: &str, version: Option<&str>, dev: bool) -> Result<()> {
+    match target {
+        DependencyTarget::PackageJson(path) => add_to_package_json(path, name, version, dev),
+        DependencyTarget::RequirementsTxt(path) => add_to_requirements_txt(path, name, version),
+        DependencyTarget::Pep723Script(path) => add_to_pep723(path, name, version),
+    }
+}
+
+pub fn remove(target: &DependencyTarget, name: &str, dev: bool) -> Result<()> {
+    match target {
+        DependencyTarget::PackageJson(path) => remove_from_package_json(path, name, dev),
+        DependencyTarget::RequirementsTxt(path) => remove_from_requirements_txt(path, name),
+        DependencyTarget::Pep723Script(path) => remove_from_pep723(path, name),
+    }
+}
+
+fn format_requirement(name: &str, version: Option<&str>) -> String {
+    match version {
+        Some(v) => format!("{}=={}", name, v),
+        None => name.to_string(),
+    }
+}
+
+/// The package name a requirement line names, ignoring any version
+/// specifier, extras, or environment marker (`"requests<3"` -> `"requests"`).
+fn requirement_package_name(requirement: &str) -> &str {
+    requirement.trim()
+        .split(|c: char| "=<>!~[; ".contains(c))
+        .next()
+        .unwrap_or(requirement)
+        .trim()
+}
+
+fn read_package_json(path: &Path) -> Result<serde_json::Value> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read '{}': {}", path.display(), e))?;
+    serde_json::from_str(&raw)
+        .map_err(|e| anyhow!("Failed to parse '{}': {}", path.display(), e))
+}
+
+fn write_package_json(path: &Path, manifest: &serde_json::Value) -> Result<()> {
+    let json = serde_json::to_string_pretty(manifest)
+        .map_err(|e| anyhow!("Failed to serialize '{}': {}", path.display(), e))?;
+    std::fs::write(path, json)
+        .map_err(|e| anyhow!("Failed to write '{}': {}", path.display(), e))
+}
+
+fn add_to_package_json(path: &Path, name: &str, version: Option<&str>, dev: bool) -> Result<()> {
+    let mut manifest = read_package_json(path)?;
+    let key = if dev { "devDependencies" } else { "dependencies" };
+    let section = manifest.as_object_mut()
+        .ok_or_else(|| anyhow!("'{}' is not a JSON object", path.display()))?
+        .entry(key)
+        .or_insert_with(|| serde_json::json!({}))
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("'{}' in '{}' is not a JSON object", key, path.display()))?;
+    section.insert(name.to_string(), serde_json::Value::String(version.unwrap_or("*").to_string()));
+    write_package_json(path, &manifest)
+}
+
+fn remove_from_package_json(path: &Path, name: &str, dev: bool) -> Result<()> {
+    let mut manifest = read_package_json(path)?;
+    let key = if dev { "devDependencies" } else { "dependencies" };
+    if let Some(section) = manifest.get_mut(key).and_then(|v| v.as_object_mut()) {
+        section.remove(name);
+    }
+    write_package_json(path, &manifest)
+}
+
+fn add_to_requirements_txt(path: &Path, name: &str, version: Option<&str>) -> Result<()> {
+    let content = std::fs::read_to_string(path).unwrap_or_default();
+    let mut lines: Vec<String> = content.lines()
+        .map(String::from)
+        .filter(|l| !l.trim().is_empty() && requirement_package_name(l) != name)
+        .collect();
+    lines.push(format_requirement(name, version));
+    lines.sort();
+
+    std::fs::write(path, lines.join("\n") + "\n")
+        .map_err(|e| anyhow!("Failed to write '{}': {}", path.display(), e))
+}
+
+fn remove_from_requirements_txt(path: &Path, name: &str) -> Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read '{}': {}", path.display(), e))?;
+    let lines: Vec<String> = content.lines()
+        .map(String::from)
+        .filter(|l| !l.trim().is_empty() && requirement_package_name(l) != name)
+        .collect();
+
+    std::fs::write(path, lines.join("\n") + "\n")
+        .map_err(|e| anyhow!("Failed to write '{}': {}", path.display(), e))
+}
+
+/// Locates the `# /// script` ... `# ///` fence PEP 723 defines for inline
+/// script metadata, returning the (start, end) line indices of the fence
+/// markers themselves.
+fn find_pep723_block(lines: &[String]) -> (Option<usize>, Option<usize>) {
+    let start = lines.iter().position(|l| l.trim() == "# /// script");
+    let end = start.and_then(|s| {
+        lines.iter().enumerate().skip(s + 1)
+            .find(|(_, l)| l.trim() == "# ///")
+            .map(|(i, _)| i)
+    });
+    (start, end)
+}
+
+fn parse_pep723_block(lines: &[String]) -> Result<toml::value::Table> {
+    let stripped: String = lines.iter()
+        .map(|l| {
+            let without_hash = l.trim_start_matches('#');
+            without_hash.strip_prefix(' ').unwrap_or(without_hash)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    match toml::from_str(&stripped)
+        .map_err(|e| anyhow!("Failed to parse PEP 723 metadata block: {}", e))?
+    {
+        toml::Value::Table(table) => Ok(table),
+        _ => Err(anyhow!("PEP 723 metadata block must be a TOML table")),
+    }
+}
+
+fn render_pep723_block(table: &toml::value::Table) -> Result<Vec<String>> {
+    let toml_text = toml::to_string_pretty(table)
+        .map_err(|e| anyhow!("Failed to serialize PEP 723 metadata block: {}", e))?;
+
+    let mut lines = vec!["# /// script".to_string()];
+    for line in toml_text.lines() {
+        if line.is_empty() {
+            lines.push("#".to_string());
+        } else {
+            lines.push(format!("# {}", line));
+        }
+    }
+    lines.push("# ///".to_string());
+    Ok(lines)
+}
+
+fn pep723_dependencies(table: &toml::value::Table) -> Vec<String> {
+    table.get("dependencies")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
+fn add_to_pep723(path: &Path, name: &str, version: Option<&str>) -> Result<()> {
+    let content = std::fs::read_to_string(path).unwrap_or_default();
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+
+    let (start, end) = find_pep723_block(&lines);
+    let mut table = match (start, end) {
+        (Some(s), Some(e)) => parse_pep723_block(&lines[s + 1..e])?,
+        _ => toml::value::Table::new(),
+    };
+
+    let mut dependencies = pep723_dependencies(&table);
+    let requirement = format_requirement(name, version);
+    dependencies.retain(|d| requirement_package_name(d) != name);
+    dependencies.push(requirement);
+    dependencies.sort();
+    table.insert(
+        "dependencies".to_string(),
+        toml::Value::Array(dependencies.into_iter().map(toml::Value::String).collect()),
+    );
+
+    let new_block = render_pep723_block(&table)?;
+    match (start, end) {
+        (Some(s), Some(e)) => {
+            lines.splice(s..=e, new_block);
+        }
+        _ => {
+            let insert_at = if lines.first().map(|l| l.starts_with("#!")).unwrap_or(false) { 1 } else { 0 };
+            for (offset, line) in new_block.into_iter().chain(std::iter::once(String::new())).enumerate() {
+                lines.insert(insert_at + offset, line);
+            }
+        }
+    }
+
+    std::fs::write(path, lines.join("\n") + "\n")
+        .map_err(|e| anyhow!("Failed to write '{}': {}", path.display(), e))
+}
+
+fn remove_from_pep723(path: &Path, name: &str) -> Result<()> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read '{}': {}", path.display(), e))?;
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+
+    let (start, end) = match find_pep723_block(&lines) {
+        (Some(s), Some(e)) => (s, e),
+        _ => return Ok(()),
+    };
+
+    let mut table = parse_pep723_block(&lines[start + 1..end])?;
+    let mut dependencies = pep723_dependencies(&table);
+    dependencies.retain(|d| requirement_package_name(d) != name);
+    table.insert(
+        "dependencies".to_string(),
+        toml::Value::Array(dependencies.into_iter().map(toml::Value::String).collect()),
+    );
+
+    let new_block = render_pep723_block(&table)?;
+    lines.splice(start..=end, new_block);
+
+    std::fs::write(path, lines.join("\n") + "\n")
+        .map_err(|e| anyhow!("Failed to write '{}': {}", path.display(), e))
+}
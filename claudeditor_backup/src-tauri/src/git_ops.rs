@@ -0,0 +1,114 @@
+use std::path::Path;
+
+use anyhow::{Result, anyhow};
+use git2::{IndexAddOption, Oid, Repository, Signature, StatusOptions};
+use git_status_classify::classify_status;
+
+/// Initializes a new git repository at `path`, writes a language-appropriate
+/// `.gitignore` (if one doesn't already exist), and makes the initial commit
+/// over whatever the template just wrote. This is the init/deploy step
+/// `ProjectManager` runs whenever `git_integration` turns on, whether that's
+/// at project creation or retroactively via `update_project`.
+pub fn initialize_repository(path: &str, language: &str) -> Result<()> {
+    log::info!("Initializing git repository at '{}'", path);
+
+    let repo = Repository::init(path)
+        .map_err(|e| anyhow!("Failed to initialize git repository at '{}': {}", path, e))?;
+
+    write_default_gitignore(path, language)?;
+
+    commit_all(&repo, "Initial commit")?;
+    Ok(())
+}
+
+fn write_default_gitignore(path: &str, language: &str) -> Result<()> {
+    let gitignore_path = Path::new(path).join(".gitignore");
+    if gitignore_path.exists() {
+        return Ok(());
+    }
+
+    let content = match language {
+        "typescript" | "javascript" => "node_modules/\ndist/\nbuild/\n.env\n",
+        "python" => "__pycache__/\n*.pyc\nvenv/\n.env\n",
+        "rust" => "target/\n",
+        _ => ".DS_Store\n",
+    };
+
+    std::fs::write(&gitignore_path, content)
+        .map_err(|e| anyhow!("Failed to write '{}': {}", gitignore_path.display(), e))
+}
+
+/// `repo.signature()` reads `user.name`/`user.email` from git config, which
+/// may not be set on a fresh machine; fall back to a fixed identity rather
+/// than failing the commit.
+fn signature(repo: &Repository) -> Signature<'static> {
+    repo.signature()
+        .unwrap_or_else(|_| Signature::now("ClaudEditor", "claudeditor@local")
+            .expect("static name/email is always a valid signature"))
+}
+
+fn commit_all(repo: &Repository, message: &str) -> Result<Oid> {
+    let mut index = repo.index()
+        .map_err(|e| anyhow!("Failed to open git index: {}", e))?;
+    index.add_all(["*"].iter(), IndexAddOption::DEFAULT, None)
+        .map_err(|e| anyhow!("Failed to stage changes: {}", e))?;
+    index.write()
+        .map_err(|e| anyhow!("Failed to write git index: {}", e))?;
+
+    let tree_id = index.write_tree()
+        .map_err(|e| anyhow!("Failed to write git tree: {}", e))?;
+    let tree = repo.find_tree(tree_id)
+        .map_err(|e| anyhow!("Failed to read git tree: {}", e))?;
+
+    let parents = match repo.head() {
+        Ok(head) => vec![head.peel_to_commit()
+            .map_err(|e| anyhow!("Failed to resolve HEAD commit: {}", e))?],
+        Err(_) => vec![],
+    };
+    let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+
+    let sig = signature(repo);
+    repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parent_refs)
+        .map_err(|e| anyhow!("Failed to create commit: {}", e))
+}
+
+/// Stages everything in the working tree and commits it, returning the new
+/// commit's id as a hex string.
+pub fn commit_snapshot(path: &str, message: &str) -> Result<String> {
+    let repo = Repository::open(path)
+        .map_err(|e| anyhow!("'{}' is not a git repository: {}", path, e))?;
+    commit_all(&repo, message).map(|oid| oid.to_string())
+}
+
+/// Per-file status (`"added"`/`"modified"`/`"untracked"`/`"clean"`) for
+/// every tracked or untracked file in the working tree.
+pub fn status_summary(path: &str) -> Result<Vec<(String, String)>> {
+    let repo = Repository::open(path)
+        .map_err(|e| anyhow!("'{}' is not a git repository: {}", path, e))?;
+
+    let mut options = StatusOptions::new();
+    options.include_untracked(true);
+    options.include_unmodified(true);
+
+    let statuses = repo.statuses(Some(&mut options))
+        .map_err(|e| anyhow!("Failed to read git status: {}", e))?;
+
+    Ok(statuses.iter()
+        .filter_map(|entry| entry.path().map(|p| (p.to_string(), classify_status(entry.status()))))
+        .collect())
+}
+
+/// The repository's current branch name, or `None` for a detached HEAD.
+pub fn current_branch(path: &str) -> Result<Option<String>> {
+    let repo = Repository::open(path)
+        .map_err(|e| anyhow!("'{}' is not a git repository: {}", path, e))?;
+    match repo.head() {
+        Ok(head) if head.is_branch() => Ok(head.shorthand().map(|s| s.to_string())),
+        Ok(_) => Ok(None),
+        Err(_) => Ok(None),
+    }
+}
+
+pub fn is_repository(path: &str) -> bool {
+    Repository::open(path).is_ok()
+}
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, anyhow};
+
+/// One incremental update out of a running scan job, emitted to the frontend
+/// as the `scan-progress` Tauri event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanProgress {
+    pub job_id: String,
+    pub discovered: usize,
+    pub current_path: String,
+}
+
+/// Terminal event emitted once a scan job stops, whether by finishing,
+/// being cancelled, or failing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanComplete {
+    pub job_id: String,
+    pub discovered: usize,
+    pub cancelled: bool,
+    pub error: Option<String>,
+}
+
+/// Cooperative control for a single background scan: the `spawn_blocking`
+/// loop checks `cancelled`/`paused` every iteration rather than this being a
+/// real preemptive cancellation.
+#[derive(Clone)]
+pub struct JobHandle {
+    cancelled: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
+}
+
+impl JobHandle {
+    fn new() -> Self {
+        Self {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            paused: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Relaxed);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Tracks the in-flight background scan jobs so `cancel_job`/`pause_job`/
+/// `resume_job` commands can reach a job by id without the frontend holding
+/// anything more than the string it got back from `scan_location`.
+#[derive(Default)]
+pub struct JobManager {
+    jobs: Mutex<HashMap<String, JobHandle>>,
+}
+
+impl JobManager {
+    pub fn register(&self, job_id: String) -> JobHandle {
+        let handle = JobHandle::new();
+        self.jobs.lock().unwrap().insert(job_id, handle.clone());
+        handle
+    }
+
+    /// Drops a finished job's handle so the map doesn't grow unboundedly
+    /// across a long editor session.
+    pub fn remove(&self, job_id: &str) {
+        self.jobs.lock().unwrap().remove(job_id);
+    }
+
+    pub fn cancel(&self, job_id: &str) -> Result<()> {
+        let jobs = self.jobs.lock().unwrap();
+        let handle = jobs.get(job_id).ok_or_else(|| anyhow!("Job '{}' not found", job_id))?;
+        handle.cancel();
+        Ok(())
+    }
+
+    pub fn pause(&self, job_id: &str) -> Result<()> {
+        let jobs = self.jobs.lock().unwrap();
+        let handle = jobs.get(job_id).ok_or_else(|| anyhow!("Job '{}' not found", job_id))?;
+        handle.pause();
+        Ok(())
+    }
+
+    pub fn resume(&self, job_id: &str) -> Result<()> {
+        let jobs = self.jobs.lock().unwrap();
+        let handle = jobs.get(job_id).ok_or_else(|| anyhow!("Job '{}' not found", job_id))?;
+        handle.resume();
+        Ok(())
+    }
+}
@@ -9,14 +9,18 @@ use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
 // PowerAutomation MCP Integration
-mod mcp;
 mod powerautomation;
-mod file_manager;
 mod project_manager;
 mod ai_integration;
+mod job_manager;
+mod git_ops;
+mod template_registry;
+mod dependency_manifest;
 
-use mcp::MCPCoordinator;
+use mcp_coordinator::MCPCoordinator;
 use powerautomation::PowerAutomationCore;
+use job_manager::{JobManager, ScanComplete, ScanProgress};
+use workspace_fs::{DirectoryTree, FileInfo, FileManager};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Project {
@@ -55,6 +59,7 @@ pub struct AppState {
     pub ai_agents: Mutex<HashMap<String, AIAgent>>,
     pub mcp_coordinator: Mutex<Option<MCPCoordinator>>,
     pub powerautomation_core: Mutex<Option<PowerAutomationCore>>,
+    pub job_manager: JobManager,
 }
 
 // Tauri Commands
@@ -65,7 +70,9 @@ async fn initialize_powerautomation(state: State<'_, AppState>) -> Result<String
     // Initialize components first to avoid holding locks across awaits
     let core = PowerAutomationCore::new().await.map_err(|e| e.to_string())?;
     let coordinator = MCPCoordinator::new().await.map_err(|e| e.to_string())?;
-    
+    coordinator.start().await.map_err(|e| e.to_string())?;
+    core.set_mcp_coordinator(coordinator.clone()).await;
+
     // Then update state
     {
         let mut core_guard = state.powerautomation_core.lock().unwrap();
@@ -149,45 +156,163 @@ async fn get_ai_agents(state: State<'_, AppState>) -> Result<Vec<AIAgent>, Strin
 }
 
 #[tauri::command]
-async fn read_file_content(file_path: String) -> Result<String, String> {
-    log::info!("Reading file: {}", file_path);
-    
-    std::fs::read_to_string(&file_path)
+async fn read_file_content(file_path: String, fs: State<'_, FileManager>) -> Result<String, String> {
+    fs.read_file(&file_path).await
+        .map(|content| content.text)
         .map_err(|e| format!("Failed to read file {}: {}", file_path, e))
 }
 
 #[tauri::command]
-async fn write_file_content(file_path: String, content: String) -> Result<(), String> {
-    log::info!("Writing file: {}", file_path);
-    
-    // Create parent directories if they don't exist
-    if let Some(parent) = std::path::Path::new(&file_path).parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create directories: {}", e))?;
-    }
-    
-    std::fs::write(&file_path, content)
+async fn write_file_content(file_path: String, content: String, fs: State<'_, FileManager>) -> Result<(), String> {
+    fs.write_file(&file_path, &content, None).await
         .map_err(|e| format!("Failed to write file {}: {}", file_path, e))
 }
 
 #[tauri::command]
-async fn list_directory(dir_path: String) -> Result<Vec<String>, String> {
-    log::info!("Listing directory: {}", dir_path);
-    
-    let entries = std::fs::read_dir(&dir_path)
-        .map_err(|e| format!("Failed to read directory {}: {}", dir_path, e))?;
-    
-    let mut files = Vec::new();
-    for entry in entries {
-        if let Ok(entry) = entry {
-            if let Some(name) = entry.file_name().to_str() {
-                files.push(name.to_string());
+async fn list_directory(dir_path: String, fs: State<'_, FileManager>) -> Result<Vec<String>, String> {
+    let mut names: Vec<String> = fs.list_directory(&dir_path).await
+        .map_err(|e| format!("Failed to read directory {}: {}", dir_path, e))?
+        .into_iter()
+        .map(|info| info.name)
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// Reads a file's committed `HEAD` content, for a working-tree-vs-HEAD diff.
+#[tauri::command]
+async fn read_head_content(file_path: String, fs: State<'_, FileManager>) -> Result<String, String> {
+    fs.read_head_content(&file_path).await.map_err(|e| e.to_string())
+}
+
+/// `.gitignore`-aware directory tree, for the project explorer. `overrides`
+/// force-includes specific paths that would otherwise be filtered out.
+#[tauri::command]
+async fn get_directory_tree(
+    dir_path: String,
+    max_depth: Option<usize>,
+    respect_gitignore: Option<bool>,
+    overrides: Option<Vec<String>>,
+    fs: State<'_, FileManager>,
+) -> Result<DirectoryTree, String> {
+    fs.get_directory_tree_with_options(&dir_path, max_depth, respect_gitignore.unwrap_or(true), &overrides.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// `.gitignore`-aware file name search, for the project explorer's quick-open.
+#[tauri::command]
+async fn search_files(
+    dir_path: String,
+    pattern: String,
+    max_results: Option<usize>,
+    respect_gitignore: Option<bool>,
+    overrides: Option<Vec<String>>,
+    fs: State<'_, FileManager>,
+) -> Result<Vec<FileInfo>, String> {
+    fs.search_files_with_options(&dir_path, &pattern, max_results, respect_gitignore.unwrap_or(true), &overrides.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Copies each of `sources` into `destination_dir`. The result vector is
+/// aligned with `sources`; `None` means that file copied successfully.
+#[tauri::command]
+async fn copy_files(sources: Vec<String>, destination_dir: String, fs: State<'_, FileManager>) -> Result<Vec<Option<String>>, String> {
+    Ok(fs.copy_files(sources, &destination_dir).await.into_iter().map(|r| r.err()).collect())
+}
+
+/// Moves each of `sources` into `destination_dir`. See `copy_files` for the
+/// result shape.
+#[tauri::command]
+async fn move_files(sources: Vec<String>, destination_dir: String, fs: State<'_, FileManager>) -> Result<Vec<Option<String>>, String> {
+    Ok(fs.move_files(sources, &destination_dir).await.into_iter().map(|r| r.err()).collect())
+}
+
+/// Deletes each of `paths`. See `copy_files` for the result shape.
+#[tauri::command]
+async fn delete_files(paths: Vec<String>, fs: State<'_, FileManager>) -> Result<Vec<Option<String>>, String> {
+    Ok(fs.delete_files(paths).await.into_iter().map(|r| r.err()).collect())
+}
+
+/// Spawns a cancellable background scan of `path` and returns its job id
+/// immediately. Progress streams to the frontend as `scan-progress` events
+/// and the job finishes with a `scan-complete` event; the caller never
+/// blocks waiting for the walk to finish.
+#[tauri::command]
+async fn scan_location(path: String, app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let job_id = Uuid::new_v4().to_string();
+    let handle = state.job_manager.register(job_id.clone());
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<ScanProgress>(32);
+
+    let forward_app = app.clone();
+    tokio::spawn(async move {
+        while let Some(progress) = rx.recv().await {
+            let _ = forward_app.emit_all("scan-progress", progress);
+        }
+    });
+
+    let scan_job_id = job_id.clone();
+    let scan_path = path.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut discovered = 0usize;
+        let mut cancelled = false;
+
+        for entry in walkdir::WalkDir::new(&scan_path).max_depth(10) {
+            if handle.is_cancelled() {
+                cancelled = true;
+                break;
+            }
+            while handle.is_paused() {
+                if handle.is_cancelled() {
+                    cancelled = true;
+                    break;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(100));
+            }
+            if cancelled {
+                break;
             }
+
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(_) => continue,
+            };
+
+            discovered += 1;
+            let _ = tx.blocking_send(ScanProgress {
+                job_id: scan_job_id.clone(),
+                discovered,
+                current_path: entry.path().to_string_lossy().to_string(),
+            });
         }
-    }
-    
-    files.sort();
-    Ok(files)
+
+        let _ = app.emit_all("scan-complete", ScanComplete {
+            job_id: scan_job_id.clone(),
+            discovered,
+            cancelled,
+            error: None,
+        });
+        app.state::<AppState>().job_manager.remove(&scan_job_id);
+    });
+
+    Ok(job_id)
+}
+
+#[tauri::command]
+async fn cancel_job(job_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.job_manager.cancel(&job_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn pause_job(job_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.job_manager.pause(&job_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn resume_job(job_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.job_manager.resume(&job_id).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -201,6 +326,7 @@ fn main() {
     
     tauri::Builder::default()
         .manage(AppState::default())
+        .manage(FileManager::new())
         .invoke_handler(tauri::generate_handler![
             initialize_powerautomation,
             create_project,
@@ -211,13 +337,35 @@ fn main() {
             read_file_content,
             write_file_content,
             list_directory,
+            read_head_content,
+            get_directory_tree,
+            search_files,
+            copy_files,
+            move_files,
+            delete_files,
+            scan_location,
+            cancel_job,
+            pause_job,
+            resume_job,
             get_app_version
         ])
         .setup(|app| {
             log::info!("ClaudEditor setup completed");
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::ExitRequested { .. } = event {
+                let coordinator = app_handle.state::<AppState>().mcp_coordinator.lock().unwrap().take();
+                if let Some(coordinator) = coordinator {
+                    tauri::async_runtime::block_on(async move {
+                        if let Err(e) = coordinator.shutdown().await {
+                            log::error!("Failed to shut down MCP coordinator: {}", e);
+                        }
+                    });
+                }
+            }
+        });
 }
 
@@ -3,6 +3,11 @@ use serde::{Deserialize, Serialize};
 use anyhow::{Result, anyhow};
 use tokio::sync::RwLock;
 use std::sync::Arc;
+use tracing::{info, instrument};
+
+use mcp_coordinator::MCPCoordinator;
+
+use crate::ai_integration::{AIUsage, ModelRegistryConfig, UsageTracker};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PowerAutomationConfig {
@@ -47,82 +52,96 @@ pub struct PowerAutomationCore {
     tools: Arc<RwLock<HashMap<String, ToolInfo>>>,
     agents: Arc<RwLock<HashMap<String, AgentInfo>>>,
     ai_models: Arc<RwLock<HashMap<String, AIModelConfig>>>,
+    usage: Arc<UsageTracker>,
+    mcp_coordinator: RwLock<Option<MCPCoordinator>>,
 }
 
 impl PowerAutomationCore {
     pub async fn new() -> Result<Self> {
-        log::info!("Initializing PowerAutomation Core...");
-        
+        info!("initializing PowerAutomation Core");
+
         let config = Self::load_default_config();
         let tools = Arc::new(RwLock::new(HashMap::new()));
         let agents = Arc::new(RwLock::new(HashMap::new()));
         let ai_models = Arc::new(RwLock::new(HashMap::new()));
-        
+        let usage = Arc::new(UsageTracker::default());
+
         let core = Self {
             config: config.clone(),
             tools,
             agents,
             ai_models,
+            usage,
+            mcp_coordinator: RwLock::new(None),
         };
-        
+
         // Initialize AI models
         core.initialize_ai_models().await?;
-        
+
         // Initialize default agents
         core.initialize_default_agents().await?;
-        
-        log::info!("PowerAutomation Core initialized successfully");
+
+        info!("PowerAutomation Core initialized successfully");
         Ok(core)
     }
     
+    /// Derives the default `ai_models` list from the same registry config
+    /// `AIIntegration` loads, rather than hand-maintaining a second copy that
+    /// drifts as models are added there.
     fn load_default_config() -> PowerAutomationConfig {
+        let registry = ModelRegistryConfig::builtin_defaults();
         PowerAutomationConfig {
             mcp_coordinator_port: 8080,
-            ai_models: vec![
-                AIModelConfig {
-                    name: "claude-3-5-sonnet".to_string(),
-                    model_type: "anthropic".to_string(),
-                    api_endpoint: "https://api.anthropic.com/v1/messages".to_string(),
-                    api_key: None,
-                    capabilities: vec![
-                        "text_generation".to_string(),
-                        "code_generation".to_string(),
-                        "analysis".to_string(),
-                    ],
-                },
-                AIModelConfig {
-                    name: "gpt-4".to_string(),
-                    model_type: "openai".to_string(),
-                    api_endpoint: "https://api.openai.com/v1/chat/completions".to_string(),
-                    api_key: None,
-                    capabilities: vec![
-                        "text_generation".to_string(),
-                        "code_generation".to_string(),
-                        "function_calling".to_string(),
-                    ],
-                },
-            ],
+            ai_models: Self::ai_models_from_registry(&registry),
             tools_directory: "~/.claudeditor/tools".to_string(),
             agents_directory: "~/.claudeditor/agents".to_string(),
             memory_storage_path: "~/.claudeditor/memory".to_string(),
         }
     }
+
+    /// Shared with `reload_config`, so a runtime registry reload produces the
+    /// same `AIModelConfig` shape the initial load does instead of a second,
+    /// independently-derived one.
+    fn ai_models_from_registry(registry: &ModelRegistryConfig) -> Vec<AIModelConfig> {
+        registry.available_models.iter().map(|model| AIModelConfig {
+            name: model.name.clone(),
+            model_type: model.provider.clone(),
+            api_endpoint: registry.providers.get(&model.provider)
+                .map(|p| p.base_url.clone())
+                .unwrap_or_default(),
+            api_key: None,
+            capabilities: model.capabilities.clone(),
+        }).collect()
+    }
+
+    /// Applies a fresh registry config over the current `ai_models`, the
+    /// `PowerAutomationCore` side of `AIIntegration::reload_config` — called
+    /// from there so the two registries stay in sync instead of only the
+    /// caller's copy being updated. Merges by model name like
+    /// `AIIntegration::apply_config` does, rather than replacing the set
+    /// outright.
+    pub async fn reload_config(&self, config: &ModelRegistryConfig) -> Result<()> {
+        let mut models = self.ai_models.write().await;
+        for model in Self::ai_models_from_registry(config) {
+            models.insert(model.name.clone(), model);
+        }
+
+        info!(count = models.len(), "reloaded AI models from registry config");
+        Ok(())
+    }
     
     async fn initialize_ai_models(&self) -> Result<()> {
-        log::info!("Initializing AI models...");
-        
         let mut models = self.ai_models.write().await;
         for model_config in &self.config.ai_models {
             models.insert(model_config.name.clone(), model_config.clone());
         }
-        
-        log::info!("Initialized {} AI models", models.len());
+
+        info!(count = models.len(), "initialized AI models");
         Ok(())
     }
-    
+
     async fn initialize_default_agents(&self) -> Result<()> {
-        log::info!("Initializing default agents...");
-        
+
         let mut agents = self.agents.write().await;
         
         // Agent Zero
@@ -170,13 +189,12 @@ impl PowerAutomationCore {
             status: "ready".to_string(),
         });
         
-        log::info!("Initialized {} default agents", agents.len());
+        info!(count = agents.len(), "initialized default agents");
         Ok(())
     }
-    
+
     pub async fn discover_tools(&self) -> Result<Vec<ToolInfo>> {
-        log::info!("Discovering available tools...");
-        
+
         // This would integrate with MCP-Zero tool discovery
         let mut discovered_tools = Vec::new();
         
@@ -216,7 +234,7 @@ impl PowerAutomationCore {
             tools.insert(tool.id.clone(), tool.clone());
         }
         
-        log::info!("Discovered {} tools", discovered_tools.len());
+        info!(count = discovered_tools.len(), "discovered tools");
         Ok(discovered_tools)
     }
     
@@ -235,9 +253,10 @@ impl PowerAutomationCore {
         Ok(models.values().cloned().collect())
     }
     
+    #[instrument(skip(self, task), fields(agent_id = %agent_id))]
     pub async fn execute_agent_task(&self, agent_id: &str, task: &str) -> Result<String> {
-        log::info!("Executing task '{}' with agent '{}'", task, agent_id);
-        
+        info!("executing agent task");
+
         let agents = self.agents.read().await;
         let agent = agents.get(agent_id)
             .ok_or_else(|| anyhow!("Agent '{}' not found", agent_id))?;
@@ -262,29 +281,93 @@ impl PowerAutomationCore {
         }
     }
     
+    /// Wires a live `MCPCoordinator` in, so `invoke_tool` can route through
+    /// its correlation-id request/response mechanism instead of simulating a
+    /// result. Called from `initialize_powerautomation` once the coordinator
+    /// has been started.
+    pub async fn set_mcp_coordinator(&self, coordinator: MCPCoordinator) {
+        *self.mcp_coordinator.write().await = Some(coordinator);
+    }
+
+    /// Dispatches a tool-calling request coming out of `AIIntegration::generate_text`.
+    /// The tool is looked up by id in the discovered-tools registry and its
+    /// `mcp_endpoint` resolved; tools without one can't be invoked this way.
+    /// Actual dispatch goes through `MCPCoordinator::execute_tool` once one
+    /// has been wired in via `set_mcp_coordinator`; until then (e.g. in tests
+    /// that construct a bare `PowerAutomationCore`), this returns a
+    /// `"status": "simulated"` response rather than claiming `"ok"` for a
+    /// call nothing actually routed anywhere.
+    #[instrument(skip(self, args), fields(tool_id = %tool_id))]
+    pub async fn invoke_tool(&self, tool_id: &str, args: serde_json::Value) -> Result<serde_json::Value> {
+        info!(%args, "invoking tool");
+
+        let (endpoint, tool_name) = {
+            let tools = self.tools.read().await;
+            let tool = tools.get(tool_id)
+                .ok_or_else(|| anyhow!("Tool '{}' not found in registry", tool_id))?;
+            let endpoint = tool.mcp_endpoint.clone()
+                .ok_or_else(|| anyhow!("Tool '{}' has no mcp_endpoint to dispatch to", tool_id))?;
+            (endpoint, tool.name.clone())
+        };
+
+        let coordinator = self.mcp_coordinator.read().await.clone();
+        let Some(coordinator) = coordinator else {
+            return Ok(serde_json::json!({
+                "tool_id": tool_id,
+                "endpoint": endpoint,
+                "status": "simulated",
+                "result": format!("Tool '{}' invoked with args {} (simulated: no MCPCoordinator wired up yet)", tool_name, args),
+            }));
+        };
+
+        let result = coordinator.execute_tool(tool_id, args).await?;
+        Ok(serde_json::json!({
+            "tool_id": tool_id,
+            "endpoint": endpoint,
+            "status": "ok",
+            "result": result,
+        }))
+    }
+
+    #[instrument(skip(self, prompt), fields(model = %model_name))]
     pub async fn call_ai_model(&self, model_name: &str, prompt: &str) -> Result<String> {
-        log::info!("Calling AI model '{}' with prompt", model_name);
-        
+        info!("calling AI model");
+
         let models = self.ai_models.read().await;
         let model = models.get(model_name)
             .ok_or_else(|| anyhow!("AI model '{}' not found", model_name))?;
-        
-        // This would integrate with the actual AI model APIs
-        match model.model_type.as_str() {
+
+        // This would integrate with the actual AI model APIs. There's no real
+        // token accounting here yet, so record a placeholder usage with zero
+        // tokens and no cost so the tracker still reflects call volume.
+        let response = match model.model_type.as_str() {
             "anthropic" => {
                 // Claude API call
-                Ok(format!("Claude response to: {}", prompt))
+                format!("Claude response to: {}", prompt)
             },
             "openai" => {
                 // OpenAI API call
-                Ok(format!("GPT response to: {}", prompt))
+                format!("GPT response to: {}", prompt)
             },
             _ => {
-                Err(anyhow!("Unknown model type: {}", model.model_type))
+                return Err(anyhow!("Unknown model type: {}", model.model_type));
             }
-        }
+        };
+
+        self.usage.record(&model.name, &model.model_type, &AIUsage {
+            prompt_tokens: 0,
+            completion_tokens: 0,
+            total_tokens: 0,
+            cost: None,
+        }).await;
+
+        Ok(response)
     }
-    
+
+    pub async fn usage_summary(&self) -> HashMap<String, AIUsage> {
+        self.usage.summary().await
+    }
+
     pub fn get_config(&self) -> &PowerAutomationConfig {
         &self.config
     }
@@ -3,7 +3,11 @@ use serde::{Deserialize, Serialize};
 use anyhow::{Result, anyhow};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use crate::dependency_manifest;
+use crate::git_ops;
+use crate::template_registry;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Project {
@@ -16,6 +20,9 @@ pub struct Project {
     pub tags: Vec<String>,
     pub settings: ProjectSettings,
     pub files: Vec<ProjectFile>,
+    /// Template the project was scaffolded from, if any. Needed by
+    /// `ProjectManager::set_feature` to look up the feature's files/patches.
+    pub template_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +33,14 @@ pub struct ProjectSettings {
     pub auto_save: bool,
     pub git_integration: bool,
     pub mcp_tools: Vec<String>,
+    /// Names of the template features currently toggled on, so
+    /// `set_feature` stays idempotent across sessions.
+    pub enabled_features: Vec<String>,
+    /// Extra gitignore-style glob patterns `scan_project_files` excludes on
+    /// top of `.gitignore`/`.claudeditorignore`, e.g. project-specific
+    /// generated-file globs.
+    #[serde(default)]
+    pub ignore_patterns: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +61,15 @@ pub struct ProjectTemplate {
     pub language: String,
     pub framework: Option<String>,
     pub files: Vec<TemplateFile>,
+    /// Optional named feature layers (e.g. `redis`, `jwt`) that can be
+    /// toggled on an existing project via `ProjectManager::set_feature`,
+    /// instead of only being applied once at creation.
+    pub features: HashMap<String, TemplateFeature>,
+    /// Preset-manifest style lifecycle scripts, e.g. an `npm install` or
+    /// `python -m venv` that needs to run after scaffolding rather than only
+    /// dropping static files.
+    #[serde(default)]
+    pub lifecycle: LifecycleHooks,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,9 +79,62 @@ pub struct TemplateFile {
     pub is_template: bool,
 }
 
+/// The lifecycle script blocks a template can declare. Currently only
+/// `pre_create`/`post_create` are run by `ProjectManager` (around
+/// `apply_template`); `pre_build`/`post_build` are reserved for a future
+/// build-command hook point.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LifecycleHooks {
+    pub pre_create: Option<LifecyclePhase>,
+    pub post_create: Option<LifecyclePhase>,
+    pub pre_build: Option<LifecyclePhase>,
+    pub post_build: Option<LifecyclePhase>,
+}
+
+/// One lifecycle script, run via the platform shell in the project
+/// directory after `${VAR}` env-var expansion. `{{project_name}}`/
+/// `{{description}}` placeholders are not substituted in the script; the
+/// project name and description reach it as the `PROJECT_NAME`/
+/// `PROJECT_DESCRIPTION` environment variables. `notes` is printed to the log before the script runs,
+/// for operator-facing context (e.g. "installing npm dependencies...").
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LifecyclePhase {
+    pub script: String,
+    pub notes: Option<String>,
+}
+
+/// One togglable feature layer: its own files plus patch fragments merged
+/// into shared manifest files (e.g. `package.json`'s `dependencies`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateFeature {
+    pub name: String,
+    pub description: String,
+    pub files: Vec<TemplateFile>,
+    pub manifest_patches: Vec<ManifestPatch>,
+}
+
+/// A JSON fragment merged into `target_file`'s `merge_key` object when the
+/// owning feature is enabled, and removed key-by-key when it's disabled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestPatch {
+    pub target_file: String,
+    pub merge_key: String,
+    pub fragment: serde_json::Value,
+}
+
+/// A bulk operation `ProjectManager::run_on_tag` can apply across every
+/// project tagged with a given tag, modeled on fw's tag-driven workspace
+/// commands.
+pub enum TagOperation {
+    Rescan,
+    GitCommit(String),
+}
+
 pub struct ProjectManager {
     projects: HashMap<String, Project>,
     templates: HashMap<String, ProjectTemplate>,
+    /// Where `reload_templates` looks for user-provided templates, if set.
+    templates_dir: Option<PathBuf>,
 }
 
 impl ProjectManager {
@@ -65,12 +142,48 @@ impl ProjectManager {
         let mut manager = Self {
             projects: HashMap::new(),
             templates: HashMap::new(),
+            templates_dir: None,
         };
-        
+
         manager.initialize_templates();
         manager
     }
-    
+
+    /// Builds a `ProjectManager` that also loads user templates from
+    /// `templates_dir` (each a folder containing a `template.toml` manifest
+    /// plus a `files/` tree), merged alongside the built-ins. Call
+    /// `reload_templates()` later to pick up newly added templates without
+    /// restarting; templates already loaded are not refreshed.
+    pub fn with_templates_dir(templates_dir: impl Into<PathBuf>) -> Self {
+        let mut manager = Self::new();
+        manager.templates_dir = Some(templates_dir.into());
+        manager.reload_templates();
+        manager
+    }
+
+    /// Re-walks the configured templates directory (if any) and merges any
+    /// templates found into the `templates` map. A loaded template whose id
+    /// collides with an existing one (built-in or previously loaded) is
+    /// dropped with a warning rather than overwriting it.
+    pub fn reload_templates(&mut self) {
+        let templates_dir = match &self.templates_dir {
+            Some(dir) => dir.clone(),
+            None => return,
+        };
+
+        for template in template_registry::load_template_registry(&templates_dir.to_string_lossy()) {
+            if self.templates.contains_key(&template.id) {
+                log::warn!(
+                    "Ignoring template '{}' from '{}': id already in use",
+                    template.id,
+                    templates_dir.display()
+                );
+                continue;
+            }
+            self.templates.insert(template.id.clone(), template);
+        }
+    }
+
     fn initialize_templates(&mut self) {
         // React TypeScript Template
         self.templates.insert("react_typescript".to_string(), ProjectTemplate {
@@ -120,8 +233,18 @@ export default App;"#.to_string(),
                     is_template: true,
                 },
             ],
+            features: HashMap::new(),
+            lifecycle: LifecycleHooks {
+                pre_create: None,
+                post_create: Some(LifecyclePhase {
+                    script: "npm install".to_string(),
+                    notes: Some("Installing npm dependencies...".to_string()),
+                }),
+                pre_build: None,
+                post_build: None,
+            },
         });
-        
+
         // Python Template
         self.templates.insert("python".to_string(), ProjectTemplate {
             id: "python".to_string(),
@@ -172,8 +295,18 @@ python main.py
                     is_template: true,
                 },
             ],
+            features: HashMap::new(),
+            lifecycle: LifecycleHooks {
+                pre_create: None,
+                post_create: Some(LifecyclePhase {
+                    script: "python3 -m venv venv".to_string(),
+                    notes: Some("Creating a virtualenv in venv/...".to_string()),
+                }),
+                pre_build: None,
+                post_build: None,
+            },
         });
-        
+
         // Node.js Template
         self.templates.insert("nodejs".to_string(), ProjectTemplate {
             id: "nodejs".to_string(),
@@ -221,21 +354,103 @@ app.listen(port, () => {
                     is_template: true,
                 },
             ],
+            features: HashMap::from([
+                ("redis".to_string(), TemplateFeature {
+                    name: "redis".to_string(),
+                    description: "Redis client wired up for caching/sessions".to_string(),
+                    files: vec![
+                        TemplateFile {
+                            path: "redis.js".to_string(),
+                            content: r#"const { createClient } = require('redis');
+
+const redisClient = createClient({ url: process.env.REDIS_URL || 'redis://localhost:6379' });
+redisClient.on('error', (err) => console.error('Redis client error', err));
+
+module.exports = redisClient;
+"#.to_string(),
+                            is_template: false,
+                        },
+                    ],
+                    manifest_patches: vec![
+                        ManifestPatch {
+                            target_file: "package.json".to_string(),
+                            merge_key: "dependencies".to_string(),
+                            fragment: serde_json::json!({ "redis": "^4.6.10" }),
+                        },
+                    ],
+                }),
+                ("jwt".to_string(), TemplateFeature {
+                    name: "jwt".to_string(),
+                    description: "JSON Web Token authentication middleware".to_string(),
+                    files: vec![
+                        TemplateFile {
+                            path: "middleware/auth.js".to_string(),
+                            content: r#"const jwt = require('jsonwebtoken');
+
+function requireAuth(req, res, next) {
+  const header = req.headers.authorization || '';
+  const token = header.startsWith('Bearer ') ? header.slice(7) : null;
+  if (!token) {
+    return res.status(401).json({ error: 'Missing bearer token' });
+  }
+
+  try {
+    req.user = jwt.verify(token, process.env.JWT_SECRET);
+    next();
+  } catch (err) {
+    res.status(401).json({ error: 'Invalid token' });
+  }
+}
+
+module.exports = { requireAuth };
+"#.to_string(),
+                            is_template: false,
+                        },
+                    ],
+                    manifest_patches: vec![
+                        ManifestPatch {
+                            target_file: "package.json".to_string(),
+                            merge_key: "dependencies".to_string(),
+                            fragment: serde_json::json!({ "jsonwebtoken": "^9.0.2" }),
+                        },
+                    ],
+                }),
+            ]),
+            lifecycle: LifecycleHooks {
+                pre_create: None,
+                post_create: Some(LifecyclePhase {
+                    script: "npm install".to_string(),
+                    notes: Some("Installing npm dependencies...".to_string()),
+                }),
+                pre_build: None,
+                post_build: None,
+            },
         });
     }
-    
-    pub fn create_project(&mut self, name: String, path: String, description: Option<String>, template_id: Option<String>) -> Result<Project> {
+
+    pub fn create_project(
+        &mut self,
+        name: String,
+        path: String,
+        description: Option<String>,
+        template_id: Option<String>,
+        git_integration: bool,
+    ) -> Result<Project> {
         log::info!("Creating project '{}' at '{}'", name, path);
-        
+
         // Check if project already exists
         if self.projects.values().any(|p| p.path == path) {
             return Err(anyhow!("Project already exists at path '{}'", path));
         }
-        
+
         // Create project directory
         std::fs::create_dir_all(&path)
             .map_err(|e| anyhow!("Failed to create project directory: {}", e))?;
-        
+
+        let language = template_id.as_ref()
+            .and_then(|id| self.templates.get(id))
+            .map(|template| template.language.clone());
+
         let project = Project {
             id: Uuid::new_v4().to_string(),
             name: name.clone(),
@@ -245,61 +460,321 @@ app.listen(port, () => {
             last_modified: Utc::now(),
             tags: vec![],
             settings: ProjectSettings {
-                language: None,
+                language: language.clone(),
                 framework: None,
                 ai_model_preference: Some("claude-3-5-sonnet".to_string()),
                 auto_save: true,
-                git_integration: false,
+                git_integration,
                 mcp_tools: vec![],
+                enabled_features: vec![],
+                ignore_patterns: vec![],
             },
             files: vec![],
+            template_id: template_id.clone(),
         };
-        
+
         // Apply template if specified
         if let Some(template_id) = template_id {
             self.apply_template(&project, &template_id)?;
         }
-        
+
+        // git_init/deploy step: initialize the repo, write a language-appropriate
+        // .gitignore, and make the initial commit once templates have landed.
+        if git_integration {
+            git_ops::initialize_repository(&project.path, language.as_deref().unwrap_or("unknown"))?;
+        }
+
         // Save project
         self.projects.insert(project.id.clone(), project.clone());
         self.save_project_metadata(&project)?;
-        
+
         log::info!("Project '{}' created successfully", project.id);
         Ok(project)
     }
-    
+
     fn apply_template(&self, project: &Project, template_id: &str) -> Result<()> {
         log::info!("Applying template '{}' to project '{}'", template_id, project.name);
-        
+
         let template = self.templates.get(template_id)
             .ok_or_else(|| anyhow!("Template '{}' not found", template_id))?;
-        
-        for template_file in &template.files {
-            let file_path = Path::new(&project.path).join(&template_file.path);
-            
+
+        if let Some(phase) = &template.lifecycle.pre_create {
+            Self::run_lifecycle_phase(project, phase)?;
+        }
+
+        Self::write_template_files(&project.path, &project.name, project.description.as_deref(), &template.files)?;
+
+        if let Some(phase) = &template.lifecycle.post_create {
+            Self::run_lifecycle_phase(project, phase)?;
+        }
+
+        Ok(())
+    }
+
+    /// Runs one lifecycle script in the project directory: logs `phase.notes`
+    /// as an operator-facing status line, expands `${VAR}` env references in
+    /// the script, then captures its stdout/stderr into the log.
+    ///
+    /// `project_name`/`description` are passed to the script as the
+    /// `PROJECT_NAME`/`PROJECT_DESCRIPTION` environment variables rather than
+    /// substituted into the script text, since both come from unvalidated
+    /// frontend input (`create_project`'s `name`/`description`) and
+    /// interpolating them into a string handed to `sh -c`/`cmd /C` would let
+    /// a project name like `` `rm -rf ~` `` execute arbitrary commands.
+    fn run_lifecycle_phase(project: &Project, phase: &LifecyclePhase) -> Result<()> {
+        if let Some(notes) = &phase.notes {
+            log::info!("{}", notes);
+        }
+
+        let script = expand_env_vars(&phase.script);
+
+        let mut command = if cfg!(target_os = "windows") {
+            let mut cmd = std::process::Command::new("cmd");
+            cmd.arg("/C").arg(&script);
+            cmd
+        } else {
+            let mut cmd = std::process::Command::new("sh");
+            cmd.arg("-c").arg(&script);
+            cmd
+        };
+
+        command
+            .env("PROJECT_NAME", &project.name)
+            .env("PROJECT_DESCRIPTION", project.description.as_deref().unwrap_or(""));
+
+        let output = command.current_dir(&project.path).output()
+            .map_err(|e| anyhow!("Failed to run lifecycle script '{}': {}", script, e))?;
+
+        if !output.stdout.is_empty() {
+            log::info!("{}", String::from_utf8_lossy(&output.stdout));
+        }
+        if !output.stderr.is_empty() {
+            log::warn!("{}", String::from_utf8_lossy(&output.stderr));
+        }
+
+        if !output.status.success() {
+            return Err(anyhow!("Lifecycle script '{}' exited with status {}", script, output.status));
+        }
+
+        Ok(())
+    }
+
+    fn write_template_files(project_path: &str, project_name: &str, description: Option<&str>, files: &[TemplateFile]) -> Result<()> {
+        for template_file in files {
+            let file_path = Path::new(project_path).join(&template_file.path);
+
             // Create parent directories
             if let Some(parent) = file_path.parent() {
                 std::fs::create_dir_all(parent)
                     .map_err(|e| anyhow!("Failed to create directories: {}", e))?;
             }
-            
+
             // Process template content
             let content = if template_file.is_template {
                 template_file.content
-                    .replace("{{project_name}}", &project.name)
-                    .replace("{{description}}", &project.description.as_deref().unwrap_or(""))
+                    .replace("{{project_name}}", project_name)
+                    .replace("{{description}}", description.unwrap_or(""))
             } else {
                 template_file.content.clone()
             };
-            
+
             // Write file
             std::fs::write(&file_path, content)
                 .map_err(|e| anyhow!("Failed to write template file '{}': {}", file_path.display(), e))?;
         }
-        
+
+        Ok(())
+    }
+
+    fn remove_feature_files(project_path: &str, files: &[TemplateFile]) -> Result<()> {
+        for template_file in files {
+            let file_path = Path::new(project_path).join(&template_file.path);
+            if file_path.exists() {
+                std::fs::remove_file(&file_path)
+                    .map_err(|e| anyhow!("Failed to remove feature file '{}': {}", file_path.display(), e))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_manifest_patch(project_path: &str, patch: &ManifestPatch) -> Result<()> {
+        let target_path = Path::new(project_path).join(&patch.target_file);
+        let mut manifest: serde_json::Value = if target_path.exists() {
+            let raw = std::fs::read_to_string(&target_path)
+                .map_err(|e| anyhow!("Failed to read '{}': {}", target_path.display(), e))?;
+            serde_json::from_str(&raw)
+                .map_err(|e| anyhow!("Failed to parse '{}': {}", target_path.display(), e))?
+        } else {
+            serde_json::json!({})
+        };
+
+        let fragment = patch.fragment.as_object()
+            .ok_or_else(|| anyhow!("Manifest patch fragment for '{}' must be a JSON object", patch.merge_key))?;
+
+        let section = manifest.as_object_mut()
+            .ok_or_else(|| anyhow!("'{}' does not contain a JSON object", target_path.display()))?
+            .entry(patch.merge_key.clone())
+            .or_insert_with(|| serde_json::json!({}));
+        let section = section.as_object_mut()
+            .ok_or_else(|| anyhow!("'{}' in '{}' is not a JSON object", patch.merge_key, target_path.display()))?;
+
+        for (key, value) in fragment {
+            section.insert(key.clone(), value.clone());
+        }
+
+        Self::write_manifest(&target_path, &manifest)
+    }
+
+    /// Removes exactly the keys `apply_manifest_patch` added, leaving any
+    /// other entries in `merge_key` (added by the user or another feature)
+    /// untouched.
+    fn revert_manifest_patch(project_path: &str, patch: &ManifestPatch) -> Result<()> {
+        let target_path = Path::new(project_path).join(&patch.target_file);
+        if !target_path.exists() {
+            return Ok(());
+        }
+
+        let raw = std::fs::read_to_string(&target_path)
+            .map_err(|e| anyhow!("Failed to read '{}': {}", target_path.display(), e))?;
+        let mut manifest: serde_json::Value = serde_json::from_str(&raw)
+            .map_err(|e| anyhow!("Failed to parse '{}': {}", target_path.display(), e))?;
+
+        let fragment = patch.fragment.as_object()
+            .ok_or_else(|| anyhow!("Manifest patch fragment for '{}' must be a JSON object", patch.merge_key))?;
+
+        if let Some(section) = manifest.get_mut(&patch.merge_key).and_then(|v| v.as_object_mut()) {
+            for key in fragment.keys() {
+                section.remove(key);
+            }
+        }
+
+        Self::write_manifest(&target_path, &manifest)
+    }
+
+    fn write_manifest(path: &Path, manifest: &serde_json::Value) -> Result<()> {
+        let json = serde_json::to_string_pretty(manifest)
+            .map_err(|e| anyhow!("Failed to serialize '{}': {}", path.display(), e))?;
+        std::fs::write(path, json)
+            .map_err(|e| anyhow!("Failed to write '{}': {}", path.display(), e))
+    }
+
+    /// Turns a named template feature on or off for an existing project:
+    /// writes (or removes) the feature's own files and merges (or reverts)
+    /// its manifest patches. Idempotent — re-enabling an already-enabled
+    /// feature, or disabling one that's already off, is a no-op.
+    pub fn set_feature(&mut self, project_id: &str, feature: &str, enabled: bool) -> Result<()> {
+        let project = self.projects.get(project_id)
+            .ok_or_else(|| anyhow!("Project '{}' not found", project_id))?;
+
+        let template_id = project.template_id.clone()
+            .ok_or_else(|| anyhow!("Project '{}' was not created from a template", project_id))?;
+        let template = self.templates.get(&template_id)
+            .ok_or_else(|| anyhow!("Template '{}' not found", template_id))?;
+        let template_feature = template.features.get(feature)
+            .ok_or_else(|| anyhow!("Template '{}' has no feature '{}'", template_id, feature))?
+            .clone();
+
+        let already_enabled = project.settings.enabled_features.iter().any(|f| f == feature);
+        if enabled == already_enabled {
+            return Ok(());
+        }
+
+        let project_path = project.path.clone();
+        let project_name = project.name.clone();
+        let project_description = project.description.clone();
+
+        if enabled {
+            Self::write_template_files(&project_path, &project_name, project_description.as_deref(), &template_feature.files)?;
+            for patch in &template_feature.manifest_patches {
+                Self::apply_manifest_patch(&project_path, patch)?;
+            }
+        } else {
+            Self::remove_feature_files(&project_path, &template_feature.files)?;
+            for patch in &template_feature.manifest_patches {
+                Self::revert_manifest_patch(&project_path, patch)?;
+            }
+        }
+
+        let project = self.projects.get_mut(project_id)
+            .ok_or_else(|| anyhow!("Project '{}' not found", project_id))?;
+        if enabled {
+            project.settings.enabled_features.push(feature.to_string());
+        } else {
+            project.settings.enabled_features.retain(|f| f != feature);
+        }
+        project.last_modified = Utc::now();
+
+        let project_clone = project.clone();
+        self.save_project_metadata(&project_clone)?;
+
+        log::info!(
+            "Feature '{}' {} for project '{}'",
+            feature,
+            if enabled { "enabled" } else { "disabled" },
+            project_id
+        );
         Ok(())
     }
     
+    /// Adds `name` (optionally pinned to `version`) to the project's
+    /// dependency manifest — `package.json`'s `dependencies`/
+    /// `devDependencies` for a JS/TS project, a pinned line in
+    /// `requirements.txt` for Python, or the PEP 723 inline metadata block
+    /// for a single-file Python script — and returns the updated
+    /// `ProjectFile` entry for that manifest.
+    pub fn add_dependency(&mut self, project_id: &str, name: &str, version: Option<&str>, dev: bool) -> Result<ProjectFile> {
+        let project = self.projects.get(project_id)
+            .ok_or_else(|| anyhow!("Project '{}' not found", project_id))?;
+        let target = dependency_manifest::locate(&project.path, project.settings.language.as_deref())?;
+
+        dependency_manifest::add(&target, name, version, dev)?;
+
+        self.finish_dependency_edit(project_id, target.path())
+    }
+
+    /// The inverse of `add_dependency`: strips `name` from the project's
+    /// dependency manifest, wherever it lives.
+    pub fn remove_dependency(&mut self, project_id: &str, name: &str, dev: bool) -> Result<ProjectFile> {
+        let project = self.projects.get(project_id)
+            .ok_or_else(|| anyhow!("Project '{}' not found", project_id))?;
+        let target = dependency_manifest::locate(&project.path, project.settings.language.as_deref())?;
+
+        dependency_manifest::remove(&target, name, dev)?;
+
+        self.finish_dependency_edit(project_id, target.path())
+    }
+
+    /// Refreshes `last_modified`, persists metadata, and builds the
+    /// `ProjectFile` entry for the manifest `add_dependency`/
+    /// `remove_dependency` just edited.
+    fn finish_dependency_edit(&mut self, project_id: &str, manifest_path: &Path) -> Result<ProjectFile> {
+        let project = self.projects.get_mut(project_id)
+            .ok_or_else(|| anyhow!("Project '{}' not found", project_id))?;
+        project.last_modified = Utc::now();
+
+        let metadata = manifest_path.metadata()
+            .map_err(|e| anyhow!("Failed to read metadata for '{}': {}", manifest_path.display(), e))?;
+        let relative_path = manifest_path.strip_prefix(&project.path).unwrap_or(manifest_path);
+
+        let file = ProjectFile {
+            path: relative_path.to_string_lossy().to_string(),
+            name: manifest_path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+            file_type: manifest_path.extension()
+                .map(|ext| ext.to_string_lossy().to_string())
+                .unwrap_or_else(|| "unknown".to_string()),
+            size: metadata.len(),
+            last_modified: metadata.modified()
+                .map_err(|e| anyhow!("Failed to get modification time: {}", e))?
+                .into(),
+            is_open: false,
+        };
+
+        let project_clone = project.clone();
+        self.save_project_metadata(&project_clone)?;
+
+        Ok(file)
+    }
+
     pub fn get_project(&self, project_id: &str) -> Option<&Project> {
         self.projects.get(project_id)
     }
@@ -310,34 +785,44 @@ app.listen(port, () => {
     
     pub fn update_project(&mut self, project_id: &str, updates: ProjectUpdates) -> Result<()> {
         log::info!("Updating project '{}'", project_id);
-        
+
         let project = self.projects.get_mut(project_id)
             .ok_or_else(|| anyhow!("Project '{}' not found", project_id))?;
-        
+
+        let git_integration_was_on = project.settings.git_integration;
+
         if let Some(name) = updates.name {
             project.name = name;
         }
-        
+
         if let Some(description) = updates.description {
             project.description = Some(description);
         }
-        
+
         if let Some(tags) = updates.tags {
             project.tags = tags;
         }
-        
+
         if let Some(settings) = updates.settings {
             project.settings = settings;
         }
-        
+
         project.last_modified = Utc::now();
-        
-        // Clone project data for metadata saving to avoid borrowing issues  
+
+        // Turning git_integration on retroactively runs the same init/deploy
+        // step `create_project` runs up front, unless the project is
+        // already a repo (e.g. it was toggled on, off, then back on).
+        if !git_integration_was_on && project.settings.git_integration && !git_ops::is_repository(&project.path) {
+            let language = project.settings.language.clone();
+            git_ops::initialize_repository(&project.path, language.as_deref().unwrap_or("unknown"))?;
+        }
+
+        // Clone project data for metadata saving to avoid borrowing issues
         let project_clone = project.clone();
-        
+
         // Save updated metadata
         self.save_project_metadata(&project_clone)?;
-        
+
         Ok(())
     }
     
@@ -357,29 +842,45 @@ app.listen(port, () => {
         Ok(())
     }
     
+    /// Walks the project tree, honoring `.gitignore`, a top-level
+    /// `.claudeditorignore`, and `settings.ignore_patterns`, and records the
+    /// surviving files on `project.files`. Ignore precedence follows
+    /// gitignore's usual nearest-ancestor-wins rule; `ignore_patterns` are
+    /// compiled into their own matcher once per scan and applied on top, so
+    /// they behave like a project-wide `.gitignore` entry regardless of
+    /// which directory a file lives in.
     pub fn scan_project_files(&mut self, project_id: &str) -> Result<()> {
         log::info!("Scanning files for project '{}'", project_id);
-        
+
         let project = self.projects.get_mut(project_id)
             .ok_or_else(|| anyhow!("Project '{}' not found", project_id))?;
-        
+
+        let extra_ignore = Self::build_extra_ignore(&project.path, &project.settings.ignore_patterns)?;
+
         let mut files = Vec::new();
-        
-        for entry in walkdir::WalkDir::new(&project.path).max_depth(10) {
+
+        let mut walker = ignore::WalkBuilder::new(&project.path);
+        walker.add_custom_ignore_filename(".claudeditorignore");
+        walker.max_depth(Some(10));
+
+        for entry in walker.build() {
             let entry = entry.map_err(|e| anyhow!("Walk error: {}", e))?;
             let path = entry.path();
-            
-            // Skip directories and hidden files
-            if path.is_dir() || path.file_name().unwrap_or_default().to_string_lossy().starts_with('.') {
+
+            let is_dir = entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false);
+            if is_dir {
                 continue;
             }
-            
+            if extra_ignore.matched_path_or_any_parents(path, false).is_ignore() {
+                continue;
+            }
+
             let metadata = path.metadata()
                 .map_err(|e| anyhow!("Failed to read metadata: {}", e))?;
-            
+
             let relative_path = path.strip_prefix(&project.path)
                 .map_err(|e| anyhow!("Failed to get relative path: {}", e))?;
-            
+
             let file = ProjectFile {
                 path: relative_path.to_string_lossy().to_string(),
                 name: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
@@ -392,30 +893,161 @@ app.listen(port, () => {
                     .into(),
                 is_open: false,
             };
-            
+
             files.push(file);
         }
-        
+
         project.files = files;
         project.last_modified = Utc::now();
-        
-        // Clone project data for metadata saving to avoid borrowing issues  
+
+        // Clone project data for metadata saving to avoid borrowing issues
         let project_clone = project.clone();
-        
+
         // Save updated metadata
         self.save_project_metadata(&project_clone)?;
-        
+
         // Get file count and project name for logging
         let file_count = project_clone.files.len();
         let project_name = project_clone.name.clone();
-        
+
         log::info!("Scanned {} files for project '{}'", file_count, project_name);
         Ok(())
     }
+
+    /// Compiles `patterns` (gitignore-style globs from
+    /// `ProjectSettings::ignore_patterns`) into a matcher relative to
+    /// `project_path`, so `scan_project_files` can test each entry against
+    /// the user's extra ignores on top of `.gitignore`/`.claudeditorignore`.
+    fn build_extra_ignore(project_path: &str, patterns: &[String]) -> Result<ignore::gitignore::Gitignore> {
+        let mut builder = ignore::gitignore::GitignoreBuilder::new(project_path);
+        for pattern in patterns {
+            builder.add_line(None, pattern)
+                .map_err(|e| anyhow!("Invalid ignore pattern '{}': {}", pattern, e))?;
+        }
+        builder.build().map_err(|e| anyhow!("Failed to compile ignore patterns: {}", e))
+    }
     
     pub fn get_templates(&self) -> Vec<&ProjectTemplate> {
         self.templates.values().collect()
     }
+
+    /// Every project currently tagged with `tag`.
+    pub fn projects_by_tag(&self, tag: &str) -> Vec<&Project> {
+        self.projects.values().filter(|p| p.tags.iter().any(|t| t == tag)).collect()
+    }
+
+    /// Every tag currently in use across all projects, with how many
+    /// projects carry it.
+    pub fn all_tags(&self) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for project in self.projects.values() {
+            for tag in &project.tags {
+                *counts.entry(tag.clone()).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    /// Runs `op` across every project tagged with `tag`, continuing past
+    /// individual failures rather than aborting the whole batch, and
+    /// reports each project's outcome by id.
+    pub fn run_on_tag(&mut self, tag: &str, op: TagOperation) -> Vec<(String, Result<()>)> {
+        let project_ids: Vec<String> = self.projects_by_tag(tag).into_iter().map(|p| p.id.clone()).collect();
+
+        project_ids.into_iter()
+            .map(|id| {
+                let result = match &op {
+                    TagOperation::Rescan => self.scan_project_files(&id),
+                    TagOperation::GitCommit(message) => self.commit_snapshot(&id, message).map(|_| ()),
+                };
+                (id, result)
+            })
+            .collect()
+    }
+
+    /// Walks `roots` for `.claudeditor/project.json` files and registers any
+    /// project not already held in memory, so tag queries span the user's
+    /// whole machine rather than only projects opened this session. Returns
+    /// how many new projects were registered.
+    pub fn discover_workspace(&mut self, roots: &[String]) -> Result<usize> {
+        let mut discovered = 0;
+
+        for root in roots {
+            for entry in walkdir::WalkDir::new(root).max_depth(6) {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(_) => continue,
+                };
+
+                if entry.file_name() != "project.json" {
+                    continue;
+                }
+                if entry.path().parent().and_then(|p| p.file_name()) != Some(std::ffi::OsStr::new(".claudeditor")) {
+                    continue;
+                }
+
+                let metadata_content = match std::fs::read_to_string(entry.path()) {
+                    Ok(content) => content,
+                    Err(e) => {
+                        log::warn!("Failed to read '{}': {}", entry.path().display(), e);
+                        continue;
+                    }
+                };
+
+                let mut project: Project = match serde_json::from_str(&metadata_content) {
+                    Ok(project) => project,
+                    Err(e) => {
+                        log::warn!("Failed to parse '{}': {}", entry.path().display(), e);
+                        continue;
+                    }
+                };
+
+                if self.projects.contains_key(&project.id) {
+                    continue;
+                }
+
+                // `.claudeditor/project.json` lives two levels below the
+                // project root: `<project_root>/.claudeditor/project.json`.
+                if let Some(project_root) = entry.path().parent().and_then(|p| p.parent()) {
+                    project.path = project_root.to_string_lossy().to_string();
+                }
+
+                self.projects.insert(project.id.clone(), project);
+                discovered += 1;
+            }
+        }
+
+        Ok(discovered)
+    }
+
+    /// Stages everything in the project's working tree and commits it,
+    /// returning the new commit's id. The project must already have
+    /// `git_integration` enabled (and thus an initialized repository).
+    pub fn commit_snapshot(&self, project_id: &str, message: &str) -> Result<String> {
+        let project = self.projects.get(project_id)
+            .ok_or_else(|| anyhow!("Project '{}' not found", project_id))?;
+
+        if !project.settings.git_integration {
+            return Err(anyhow!("Project '{}' does not have git integration enabled", project_id));
+        }
+
+        git_ops::commit_snapshot(&project.path, message)
+    }
+
+    /// Per-file git status (`"added"`/`"modified"`/`"untracked"`/`"clean"`)
+    /// for the project's working tree.
+    pub fn git_status(&self, project_id: &str) -> Result<Vec<(String, String)>> {
+        let project = self.projects.get(project_id)
+            .ok_or_else(|| anyhow!("Project '{}' not found", project_id))?;
+        git_ops::status_summary(&project.path)
+    }
+
+    /// The project's current branch name, or `None` for a detached HEAD.
+    pub fn git_branch(&self, project_id: &str) -> Result<Option<String>> {
+        let project = self.projects.get(project_id)
+            .ok_or_else(|| anyhow!("Project '{}' not found", project_id))?;
+        git_ops::current_branch(&project.path)
+    }
     
     fn save_project_metadata(&self, project: &Project) -> Result<()> {
         let metadata_dir = Path::new(&project.path).join(".claudeditor");
@@ -460,7 +1092,7 @@ app.listen(port, () => {
                 .to_string_lossy()
                 .to_string();
             
-            self.create_project(name, path.to_string(), None, None)
+            self.create_project(name, path.to_string(), None, None, false)
         }
     }
 }
@@ -473,3 +1105,28 @@ pub struct ProjectUpdates {
     pub settings: Option<ProjectSettings>,
 }
 
+/// Expands `${VAR}` references against the current process environment,
+/// leaving unknown variables as an empty string. Separate from the shell's
+/// own expansion so the substituted values are visible in the log line the
+/// script came from, not just in its effects.
+fn expand_env_vars(script: &str) -> String {
+    let mut result = String::with_capacity(script.len());
+    let mut chars = script.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '$' && chars.peek() == Some(&'{') {
+            chars.next();
+            let mut name = String::new();
+            for c2 in chars.by_ref() {
+                if c2 == '}' {
+                    break;
+                }
+                name.push(c2);
+            }
+            result.push_str(&std::env::var(&name).unwrap_or_default());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
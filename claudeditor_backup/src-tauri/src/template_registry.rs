@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Result, anyhow};
+use glob_match::glob_match;
+use serde::Deserialize;
+use walkdir::WalkDir;
+
+use crate::project_manager::{LifecycleHooks, LifecyclePhase, ManifestPatch, ProjectTemplate, TemplateFeature, TemplateFile};
+
+#[derive(Debug, Deserialize)]
+struct TemplateManifest {
+    id: String,
+    name: String,
+    description: String,
+    language: String,
+    framework: Option<String>,
+    #[serde(default)]
+    template_globs: Vec<String>,
+    #[serde(default)]
+    features: HashMap<String, FeatureManifest>,
+    #[serde(default)]
+    lifecycle: LifecycleManifest,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct LifecycleManifest {
+    pre_create: Option<LifecyclePhaseManifest>,
+    post_create: Option<LifecyclePhaseManifest>,
+    pre_build: Option<LifecyclePhaseManifest>,
+    post_build: Option<LifecyclePhaseManifest>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LifecyclePhaseManifest {
+    script: String,
+    notes: Option<String>,
+}
+
+impl From<LifecyclePhaseManifest> for LifecyclePhase {
+    fn from(manifest: LifecyclePhaseManifest) -> Self {
+        Self { script: manifest.script, notes: manifest.notes }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct FeatureManifest {
+    description: String,
+    #[serde(default)]
+    template_globs: Vec<String>,
+    #[serde(default)]
+    manifest_patches: Vec<ManifestPatchManifest>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ManifestPatchManifest {
+    target_file: String,
+    merge_key: String,
+    fragment: toml::Value,
+}
+
+/// Walks `templates_dir` for folders containing a `template.toml`, the way
+/// `fw`'s `read_config` walks its projects/tags directories, loading each as
+/// a `ProjectTemplate`. A folder without that file is skipped rather than
+/// erroring, so scratch directories can live alongside real templates; a
+/// folder whose manifest fails to parse is skipped with a warning so one bad
+/// template doesn't take down the whole registry.
+pub fn load_template_registry(templates_dir: &str) -> Vec<ProjectTemplate> {
+    let mut templates = Vec::new();
+
+    for entry in WalkDir::new(templates_dir).min_depth(1).max_depth(1) {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(_) => continue,
+        };
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+
+        let manifest_path = entry.path().join("template.toml");
+        if !manifest_path.exists() {
+            continue;
+        }
+
+        match load_template(entry.path(), &manifest_path) {
+            Ok(template) => templates.push(template),
+            Err(e) => log::warn!("Skipping template at '{}': {}", entry.path().display(), e),
+        }
+    }
+
+    templates
+}
+
+fn load_template(template_dir: &Path, manifest_path: &Path) -> Result<ProjectTemplate> {
+    let raw = std::fs::read_to_string(manifest_path)
+        .map_err(|e| anyhow!("Failed to read '{}': {}", manifest_path.display(), e))?;
+    let manifest: TemplateManifest = toml::from_str(&raw)
+        .map_err(|e| anyhow!("Failed to parse '{}': {}", manifest_path.display(), e))?;
+
+    let files = collect_template_files(&template_dir.join("files"), &manifest.template_globs)?;
+
+    let mut features = HashMap::new();
+    for (feature_name, feature_manifest) in manifest.features {
+        let feature_dir = template_dir.join("features").join(&feature_name).join("files");
+        let feature_files = collect_template_files(&feature_dir, &feature_manifest.template_globs)?;
+
+        let manifest_patches = feature_manifest.manifest_patches.into_iter()
+            .map(|patch| Ok(ManifestPatch {
+                target_file: patch.target_file,
+                merge_key: patch.merge_key,
+                fragment: serde_json::to_value(&patch.fragment)
+                    .map_err(|e| anyhow!("Invalid manifest patch fragment for feature '{}': {}", feature_name, e))?,
+            }))
+            .collect::<Result<Vec<_>>>()?;
+
+        features.insert(feature_name.clone(), TemplateFeature {
+            name: feature_name,
+            description: feature_manifest.description,
+            files: feature_files,
+            manifest_patches,
+        });
+    }
+
+    Ok(ProjectTemplate {
+        id: manifest.id,
+        name: manifest.name,
+        description: manifest.description,
+        language: manifest.language,
+        framework: manifest.framework,
+        files,
+        features,
+        lifecycle: LifecycleHooks {
+            pre_create: manifest.lifecycle.pre_create.map(Into::into),
+            post_create: manifest.lifecycle.post_create.map(Into::into),
+            pre_build: manifest.lifecycle.pre_build.map(Into::into),
+            post_build: manifest.lifecycle.post_build.map(Into::into),
+        },
+    })
+}
+
+/// Reads every file under `files_dir` (if it exists) into a `TemplateFile`,
+/// with `is_template` true when its path relative to `files_dir` matches one
+/// of `template_globs`.
+fn collect_template_files(files_dir: &Path, template_globs: &[String]) -> Result<Vec<TemplateFile>> {
+    if !files_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    for entry in WalkDir::new(files_dir) {
+        let entry = entry.map_err(|e| anyhow!("Failed to walk '{}': {}", files_dir.display(), e))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let relative = entry.path().strip_prefix(files_dir)
+            .map_err(|e| anyhow!("Failed to compute relative path for '{}': {}", entry.path().display(), e))?;
+        let relative_str = relative.to_string_lossy().replace('\\', "/");
+
+        let content = std::fs::read_to_string(entry.path())
+            .map_err(|e| anyhow!("Failed to read '{}': {}", entry.path().display(), e))?;
+
+        let is_template = template_globs.iter().any(|glob| glob_match(glob, &relative_str));
+        files.push(TemplateFile { path: relative_str, content, is_template });
+    }
+
+    Ok(files)
+}
+
@@ -0,0 +1,22 @@
+//! Classifies a `git2::Status` bitflag set into the `"added"`/`"untracked"`/
+//! `"modified"`/`"clean"` strings the frontend's file tree renders, shared by
+//! every crate that surfaces git status alongside a file listing (the async
+//! `FileManager`'s `GitRepoCache` and `claudeditor_backup`'s standalone
+//! `git_ops` functions both used to keep their own copy of this).
+
+pub fn classify_status(status: git2::Status) -> String {
+    if status.contains(git2::Status::INDEX_NEW) {
+        "added".to_string()
+    } else if status.contains(git2::Status::WT_NEW) {
+        "untracked".to_string()
+    } else if status.intersects(
+        git2::Status::WT_MODIFIED | git2::Status::INDEX_MODIFIED
+            | git2::Status::WT_DELETED | git2::Status::INDEX_DELETED
+            | git2::Status::WT_RENAMED | git2::Status::INDEX_RENAMED
+            | git2::Status::WT_TYPECHANGE | git2::Status::INDEX_TYPECHANGE,
+    ) {
+        "modified".to_string()
+    } else {
+        "clean".to_string()
+    }
+}
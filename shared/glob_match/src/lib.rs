@@ -0,0 +1,64 @@
+//! A small gitignore-style glob matcher (`*`, `**`, `?`), shared by every
+//! crate in the workspace that needs to test a relative path against a
+//! glob pattern — the gitignore-aware file scanner and the template
+//! registry's template-file detection both depend on this instead of each
+//! keeping their own copy.
+
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    glob_match_bytes(pattern.as_bytes(), text.as_bytes())
+}
+
+fn glob_match_bytes(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some(b'*') if pattern.get(1) == Some(&b'*') => {
+            let mut rest = &pattern[2..];
+            if rest.first() == Some(&b'/') {
+                rest = &rest[1..];
+            }
+            (0..=text.len()).any(|i| glob_match_bytes(rest, &text[i..]))
+        }
+        Some(b'*') => {
+            let rest = &pattern[1..];
+            let limit = text.iter().position(|&b| b == b'/').unwrap_or(text.len());
+            (0..=limit).any(|i| glob_match_bytes(rest, &text[i..]))
+        }
+        Some(b'?') => !text.is_empty() && glob_match_bytes(&pattern[1..], &text[1..]),
+        Some(&c) => !text.is_empty() && text[0] == c && glob_match_bytes(&pattern[1..], &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::glob_match;
+
+    #[test]
+    fn matches_exact_literal() {
+        assert!(glob_match("Cargo.toml", "Cargo.toml"));
+        assert!(!glob_match("Cargo.toml", "cargo.toml"));
+    }
+
+    #[test]
+    fn star_matches_within_one_segment() {
+        assert!(glob_match("*.rs", "lib.rs"));
+        assert!(!glob_match("*.rs", "src/lib.rs"));
+    }
+
+    #[test]
+    fn double_star_matches_across_segments() {
+        assert!(glob_match("**/*.rs", "src/lib.rs"));
+        assert!(glob_match("**/*.rs", "lib.rs"));
+        assert!(glob_match("src/**/mod.rs", "src/a/b/mod.rs"));
+    }
+
+    #[test]
+    fn question_mark_matches_single_character() {
+        assert!(glob_match("file?.txt", "file1.txt"));
+        assert!(!glob_match("file?.txt", "file12.txt"));
+    }
+
+    #[test]
+    fn no_match_for_unrelated_text() {
+        assert!(!glob_match("*.toml", "README.md"));
+    }
+}
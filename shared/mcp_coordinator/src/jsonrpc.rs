@@ -0,0 +1,236 @@
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::mcp::{MCPMessage, MCPMessageType};
+
+const JSONRPC_VERSION: &str = "2.0";
+
+// Standard JSON-RPC 2.0 error codes (https://www.jsonrpc.org/specification#error_object).
+pub const ERROR_PARSE: i64 = -32700;
+pub const ERROR_INVALID_REQUEST: i64 = -32600;
+pub const ERROR_METHOD_NOT_FOUND: i64 = -32601;
+pub const ERROR_INVALID_PARAMS: i64 = -32602;
+pub const ERROR_INTERNAL: i64 = -32603;
+
+/// Either a single JSON value or a JSON array of them — the JSON-RPC 2.0
+/// batch convention, following the `OneOrVec` pattern from the unki API
+/// unification.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum OneOrVec<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T> OneOrVec<T> {
+    pub fn into_vec(self) -> Vec<T> {
+        match self {
+            OneOrVec::One(item) => vec![item],
+            OneOrVec::Many(items) => items,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcRequest {
+    pub jsonrpc: String,
+    pub id: Value,
+    pub method: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcResponse {
+    pub jsonrpc: String,
+    pub id: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<JsonRpcError>,
+}
+
+/// Serializes an `MCPMessage` into a spec-compliant `JsonRpcRequest`.
+/// `method` is derived from the message type (`mcp.request`/`mcp.event`/...)
+/// plus the tool id embedded in a `Request`'s payload, when present.
+pub fn to_request(message: &MCPMessage) -> JsonRpcRequest {
+    JsonRpcRequest {
+        jsonrpc: JSONRPC_VERSION.to_string(),
+        id: Value::String(message.id.clone()),
+        method: request_method(message),
+        params: Some(message.payload.clone()),
+    }
+}
+
+fn request_method(message: &MCPMessage) -> String {
+    let base = match message.message_type {
+        MCPMessageType::Request => "mcp.request",
+        MCPMessageType::Response => "mcp.response",
+        MCPMessageType::Event => "mcp.event",
+        MCPMessageType::Heartbeat => "mcp.heartbeat",
+        MCPMessageType::Registration => "mcp.registration",
+        MCPMessageType::Deregistration => "mcp.deregistration",
+    };
+
+    match message.payload.get("tool_id").and_then(|v| v.as_str()) {
+        Some(tool_id) => format!("{}.{}", base, tool_id),
+        None => base.to_string(),
+    }
+}
+
+/// Parses a `JsonRpcRequest` back into an `MCPMessage` addressed from
+/// `source` at `target`. The inverse of `to_request`.
+pub fn from_request(request: &JsonRpcRequest, source: &str, target: &str) -> Result<MCPMessage> {
+    if request.jsonrpc != JSONRPC_VERSION {
+        return Err(anyhow!("Unsupported jsonrpc version '{}'", request.jsonrpc));
+    }
+
+    let id = request.id.as_str()
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| request.id.to_string());
+
+    Ok(MCPMessage {
+        id,
+        message_type: message_type_from_method(&request.method),
+        source: source.to_string(),
+        target: target.to_string(),
+        payload: request.params.clone().unwrap_or(Value::Null),
+        timestamp: chrono::Utc::now(),
+        correlation_id: None,
+    })
+}
+
+fn message_type_from_method(method: &str) -> MCPMessageType {
+    match method.split('.').nth(1).unwrap_or(method) {
+        "request" => MCPMessageType::Request,
+        "response" => MCPMessageType::Response,
+        "event" => MCPMessageType::Event,
+        "heartbeat" => MCPMessageType::Heartbeat,
+        "registration" => MCPMessageType::Registration,
+        "deregistration" => MCPMessageType::Deregistration,
+        _ => MCPMessageType::Request,
+    }
+}
+
+/// Builds a successful `JsonRpcResponse` carrying `result`, correlated back
+/// by `id`.
+pub fn success_response(id: Value, result: Value) -> JsonRpcResponse {
+    JsonRpcResponse { jsonrpc: JSONRPC_VERSION.to_string(), id, result: Some(result), error: None }
+}
+
+/// Builds a failed `JsonRpcResponse`, correlated back by `id`.
+pub fn error_response(id: Value, code: i64, message: impl Into<String>) -> JsonRpcResponse {
+    JsonRpcResponse {
+        jsonrpc: JSONRPC_VERSION.to_string(),
+        id,
+        result: None,
+        error: Some(JsonRpcError { code, message: message.into(), data: None }),
+    }
+}
+
+/// Parses a batch (`OneOrVec`) of JSON-RPC request frames from raw JSON
+/// text, so a caller can submit a single object or a JSON array of them.
+pub fn parse_requests(raw: &str) -> Result<Vec<JsonRpcRequest>> {
+    let parsed: OneOrVec<JsonRpcRequest> = serde_json::from_str(raw)
+        .map_err(|e| anyhow!("Failed to parse JSON-RPC frame(s): {}", e))?;
+    Ok(parsed.into_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::MCPMessageType;
+
+    fn sample_message(message_type: MCPMessageType, payload: Value) -> MCPMessage {
+        MCPMessage {
+            id: "msg-1".to_string(),
+            message_type,
+            source: "coordinator".to_string(),
+            target: "some_service".to_string(),
+            payload,
+            timestamp: chrono::Utc::now(),
+            correlation_id: None,
+        }
+    }
+
+    #[test]
+    fn parse_requests_accepts_a_single_frame() {
+        let raw = r#"{"jsonrpc":"2.0","id":"1","method":"mcp.heartbeat"}"#;
+        let requests = parse_requests(raw).unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "mcp.heartbeat");
+    }
+
+    #[test]
+    fn parse_requests_accepts_a_batch() {
+        let raw = r#"[
+            {"jsonrpc":"2.0","id":"1","method":"mcp.heartbeat"},
+            {"jsonrpc":"2.0","id":"2","method":"mcp.event"}
+        ]"#;
+        let requests = parse_requests(raw).unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].id, Value::String("2".to_string()));
+    }
+
+    #[test]
+    fn parse_requests_rejects_invalid_json() {
+        assert!(parse_requests("not json").is_err());
+    }
+
+    #[test]
+    fn to_request_derives_method_from_message_type_and_tool_id() {
+        let message = sample_message(
+            MCPMessageType::Request,
+            serde_json::json!({"tool_id": "visual_editor", "parameters": {}}),
+        );
+        let request = to_request(&message);
+        assert_eq!(request.method, "mcp.request.visual_editor");
+        assert_eq!(request.id, Value::String(message.id.clone()));
+    }
+
+    #[test]
+    fn to_request_and_from_request_round_trip() {
+        let original = sample_message(MCPMessageType::Heartbeat, serde_json::json!({"ping": true}));
+        let request = to_request(&original);
+        let restored = from_request(&request, &original.source, &original.target).unwrap();
+
+        assert_eq!(restored.id, original.id);
+        assert!(matches!(restored.message_type, MCPMessageType::Heartbeat));
+        assert_eq!(restored.source, original.source);
+        assert_eq!(restored.target, original.target);
+        assert_eq!(restored.payload, original.payload);
+    }
+
+    #[test]
+    fn from_request_rejects_unsupported_jsonrpc_version() {
+        let request = JsonRpcRequest {
+            jsonrpc: "1.0".to_string(),
+            id: Value::String("1".to_string()),
+            method: "mcp.event".to_string(),
+            params: None,
+        };
+        assert!(from_request(&request, "coordinator", "some_service").is_err());
+    }
+
+    #[test]
+    fn success_and_error_response_carry_the_original_id() {
+        let id = Value::String("42".to_string());
+
+        let success = success_response(id.clone(), serde_json::json!({"ok": true}));
+        assert_eq!(success.id, id);
+        assert!(success.error.is_none());
+
+        let failure = error_response(id.clone(), ERROR_METHOD_NOT_FOUND, "no such method");
+        assert_eq!(failure.id, id);
+        assert_eq!(failure.error.unwrap().code, ERROR_METHOD_NOT_FOUND);
+    }
+}
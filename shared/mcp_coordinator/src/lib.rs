@@ -0,0 +1,16 @@
+//! `MCPCoordinator` and the transport/discovery/supervision machinery behind
+//! it, shared by every crate that needs to talk to MCP services — previously
+//! this only existed in `src-tauri`'s orphaned tree, unreachable from
+//! `claudeditor_backup`'s Tauri commands despite a `mod mcp;` declaration
+//! pointing at it.
+
+pub mod mcp;
+pub mod mcp_transport;
+pub mod mcp_discovery;
+pub mod mcp_supervisor;
+pub mod llm_provider;
+pub mod jsonrpc;
+
+pub use mcp::{
+    MCPCoordinator, MCPMessage, MCPMessageType, MCPService, MCPServiceStatus, MCPTool,
+};
@@ -0,0 +1,197 @@
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Usage accounting for a single completion call, normalized just enough to
+/// be comparable across providers even though each reports it under
+/// different field names.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LlmUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+}
+
+impl LlmUsage {
+    fn totaled(mut self) -> Self {
+        self.total_tokens = self.prompt_tokens + self.completion_tokens;
+        self
+    }
+}
+
+/// What an `LlmProvider::complete` call hands back: the provider's raw
+/// response body, untouched, alongside the usage pulled out of it.
+#[derive(Debug, Clone)]
+pub struct LlmCompletion {
+    pub raw: Value,
+    pub usage: LlmUsage,
+}
+
+/// A language-model backend. Deliberately thin and provider-specific rather
+/// than a lowest-common-denominator schema, following Zed's
+/// provider-infrastructure approach: `complete` takes a request body that's
+/// already in the provider's own shape (from `MCPTool.schema`, with a tool
+/// call's `parameters` merged in) and returns the provider's own response
+/// shape, so a newly released model works without a code change here.
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    /// The provider's own name (e.g. `"anthropic"`, `"openai"`,
+    /// `"ollama"`). `MCPTool.schema.provider` is matched against the name
+    /// passed to `MCPCoordinator::register_provider`, not against this.
+    fn name(&self) -> &str;
+
+    /// Sends `request_body` to the provider and returns its raw response
+    /// plus whatever usage accounting could be read out of it.
+    async fn complete(&self, request_body: Value) -> Result<LlmCompletion>;
+}
+
+/// Talks to the Anthropic Messages API.
+pub struct AnthropicProvider {
+    client: reqwest::Client,
+    api_key: String,
+    base_url: String,
+}
+
+impl AnthropicProvider {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key: api_key.into(),
+            base_url: "https://api.anthropic.com/v1/messages".to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for AnthropicProvider {
+    fn name(&self) -> &str {
+        "anthropic"
+    }
+
+    async fn complete(&self, request_body: Value) -> Result<LlmCompletion> {
+        let response = self.client.post(&self.base_url)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .json(&request_body)
+            .send()
+            .await
+            .map_err(|e| anyhow!("Anthropic request failed: {}", e))?;
+
+        let status = response.status();
+        let raw: Value = response.json().await
+            .map_err(|e| anyhow!("Failed to parse Anthropic response: {}", e))?;
+
+        if !status.is_success() {
+            return Err(anyhow!("Anthropic API error ({}): {}", status, raw));
+        }
+
+        let usage = LlmUsage {
+            prompt_tokens: raw.pointer("/usage/input_tokens").and_then(|v| v.as_u64()).unwrap_or(0),
+            completion_tokens: raw.pointer("/usage/output_tokens").and_then(|v| v.as_u64()).unwrap_or(0),
+            total_tokens: 0,
+        }.totaled();
+
+        Ok(LlmCompletion { raw, usage })
+    }
+}
+
+/// Talks to the OpenAI Chat Completions API.
+pub struct OpenAiProvider {
+    client: reqwest::Client,
+    api_key: String,
+    base_url: String,
+}
+
+impl OpenAiProvider {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key: api_key.into(),
+            base_url: "https://api.openai.com/v1/chat/completions".to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiProvider {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    async fn complete(&self, request_body: Value) -> Result<LlmCompletion> {
+        let response = self.client.post(&self.base_url)
+            .bearer_auth(&self.api_key)
+            .json(&request_body)
+            .send()
+            .await
+            .map_err(|e| anyhow!("OpenAI request failed: {}", e))?;
+
+        let status = response.status();
+        let raw: Value = response.json().await
+            .map_err(|e| anyhow!("Failed to parse OpenAI response: {}", e))?;
+
+        if !status.is_success() {
+            return Err(anyhow!("OpenAI API error ({}): {}", status, raw));
+        }
+
+        let usage = LlmUsage {
+            prompt_tokens: raw.pointer("/usage/prompt_tokens").and_then(|v| v.as_u64()).unwrap_or(0),
+            completion_tokens: raw.pointer("/usage/completion_tokens").and_then(|v| v.as_u64()).unwrap_or(0),
+            total_tokens: 0,
+        }.totaled();
+
+        Ok(LlmCompletion { raw, usage })
+    }
+}
+
+/// Talks to a local Ollama server's chat API. No API key; `base_url`
+/// defaults to Ollama's standard local endpoint but is overridable for a
+/// remote/Dockerized instance.
+pub struct OllamaProvider {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl OllamaProvider {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self { client: reqwest::Client::new(), base_url: base_url.into() }
+    }
+}
+
+impl Default for OllamaProvider {
+    fn default() -> Self {
+        Self::new("http://localhost:11434/api/chat")
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OllamaProvider {
+    fn name(&self) -> &str {
+        "ollama"
+    }
+
+    async fn complete(&self, request_body: Value) -> Result<LlmCompletion> {
+        let response = self.client.post(&self.base_url)
+            .json(&request_body)
+            .send()
+            .await
+            .map_err(|e| anyhow!("Ollama request failed: {}", e))?;
+
+        let status = response.status();
+        let raw: Value = response.json().await
+            .map_err(|e| anyhow!("Failed to parse Ollama response: {}", e))?;
+
+        if !status.is_success() {
+            return Err(anyhow!("Ollama API error ({}): {}", status, raw));
+        }
+
+        let usage = LlmUsage {
+            prompt_tokens: raw.get("prompt_eval_count").and_then(|v| v.as_u64()).unwrap_or(0),
+            completion_tokens: raw.get("eval_count").and_then(|v| v.as_u64()).unwrap_or(0),
+            total_tokens: 0,
+        }.totaled();
+
+        Ok(LlmCompletion { raw, usage })
+    }
+}
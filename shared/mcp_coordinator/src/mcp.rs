@@ -0,0 +1,1049 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, anyhow};
+use tokio::sync::{oneshot, watch, RwLock};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::mcp_transport::{self, Transport};
+use crate::jsonrpc;
+use crate::mcp_discovery::DiscoveryHandler;
+use crate::llm_provider::LlmProvider;
+use crate::mcp_supervisor::{OneForOnePolicy, Supervisor};
+
+/// How long `execute_tool` waits for a matching `Response` before giving up,
+/// unless overridden via `MCPCoordinator::with_request_timeout`.
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Tunables for the background heartbeat monitor spawned by
+/// `MCPCoordinator::start`. Override via `set_heartbeat_config`.
+#[derive(Debug, Clone, Copy)]
+pub struct HeartbeatConfig {
+    /// How often each registered service is pinged.
+    pub interval: Duration,
+    /// Consecutive misses before a service's status becomes `Error`
+    /// (fewer misses leave it at `Connecting`).
+    pub miss_threshold: u32,
+    /// How long since `last_heartbeat` before a service is handed to the
+    /// `Supervisor` for a restart attempt (and deregistered once its
+    /// retries are exhausted), regardless of `miss_threshold`.
+    pub stale_timeout: Duration,
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(15),
+            miss_threshold: 3,
+            stale_timeout: Duration::from_secs(120),
+        }
+    }
+}
+
+/// Tunables for the background discovery reconciliation loop spawned by
+/// `MCPCoordinator::start`. Override via `set_discovery_config`.
+#[derive(Debug, Clone, Copy)]
+pub struct DiscoveryConfig {
+    /// How often registered `DiscoveryHandler`s are polled.
+    pub interval: Duration,
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(60),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MCPService {
+    pub id: String,
+    pub name: String,
+    pub endpoint: String,
+    pub status: MCPServiceStatus,
+    pub capabilities: Vec<String>,
+    pub last_heartbeat: DateTime<Utc>,
+    pub metadata: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MCPServiceStatus {
+    Online,
+    Offline,
+    Error,
+    Connecting,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MCPMessage {
+    pub id: String,
+    pub message_type: MCPMessageType,
+    pub source: String,
+    pub target: String,
+    pub payload: serde_json::Value,
+    pub timestamp: DateTime<Utc>,
+    /// Set on a `Response` message to the `id` of the `Request` it answers,
+    /// so the transport layer can route it back to the waiting caller via
+    /// `MCPCoordinator::complete_message`. `None` for every other message
+    /// type.
+    #[serde(default)]
+    pub correlation_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MCPMessageType {
+    Request,
+    Response,
+    Event,
+    Heartbeat,
+    Registration,
+    Deregistration,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MCPTool {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub version: String,
+    pub service_id: String,
+    pub capabilities: Vec<String>,
+    pub schema: serde_json::Value,
+}
+
+// Not `Debug`: `transports` holds `Box<dyn Transport>`, which doesn't
+// implement it (the underlying child processes/sockets don't either).
+#[derive(Clone)]
+pub struct MCPCoordinator {
+    services: Arc<RwLock<HashMap<String, MCPService>>>,
+    tools: Arc<RwLock<HashMap<String, MCPTool>>>,
+    message_queue: Arc<RwLock<Vec<MCPMessage>>>,
+    /// `Request`s awaiting their `Response`, keyed by `MCPMessage.id`. Fired
+    /// by `complete_message` when the transport layer delivers the matching
+    /// `Response`.
+    pending: Arc<RwLock<HashMap<String, oneshot::Sender<serde_json::Value>>>>,
+    /// Dialed connections per service id, established via `connect_transport`
+    /// and reused by `send_message`. A target with no entry here falls back
+    /// to the shared in-memory queue (the pre-transport simulated behavior).
+    transports: Arc<RwLock<HashMap<String, Box<dyn Transport>>>>,
+    heartbeat_config: Arc<RwLock<HeartbeatConfig>>,
+    /// Consecutive missed heartbeats per service id, reset on a reply and
+    /// cleared once a service is handed to the `Supervisor` for being stale.
+    heartbeat_misses: Arc<RwLock<HashMap<String, u32>>>,
+    /// `DiscoveryHandler`s registered at runtime (e.g. a `StaticConfigHandler`
+    /// loaded from a config file), keyed by `DiscoveryHandler::id`. Fanned
+    /// out across by `discover_tools` and the background reconciliation
+    /// loop instead of services/tools being hardcoded in the binary.
+    discovery_handlers: Arc<RwLock<HashMap<String, Arc<dyn DiscoveryHandler>>>>,
+    discovery_config: Arc<RwLock<DiscoveryConfig>>,
+    /// LLM backends for tools whose `schema.provider` names one, keyed by
+    /// provider name. Registered via `register_provider`.
+    llm_providers: Arc<RwLock<HashMap<String, Box<dyn LlmProvider>>>>,
+    /// Restarts a connected service's transport with backoff when the
+    /// heartbeat monitor finds it stale, escalating to
+    /// `deregister_service` once it's out of retries.
+    supervisor: Arc<Supervisor>,
+    /// Broadcasts `true` to every background loop (heartbeat, discovery,
+    /// and by extension anything a future loop subscribes to) when
+    /// `shutdown` is called, so `start`'s tasks wind down instead of
+    /// running for the process's whole lifetime regardless of teardown.
+    shutdown_tx: watch::Sender<bool>,
+    /// Guards against `start` spawning a second set of background loops if
+    /// called more than once.
+    started: Arc<RwLock<bool>>,
+    request_timeout: Duration,
+    port: u16,
+}
+
+impl MCPCoordinator {
+    /// Builds the coordinator and its default services/tools, but starts no
+    /// background work — call `start()` once the caller is ready to bring
+    /// the whole tree up, and `shutdown()` to tear it back down.
+    pub async fn new() -> Result<Self> {
+        log::info!("Initializing MCP Coordinator...");
+
+        let (shutdown_tx, _) = watch::channel(false);
+
+        let coordinator = Self {
+            services: Arc::new(RwLock::new(HashMap::new())),
+            tools: Arc::new(RwLock::new(HashMap::new())),
+            message_queue: Arc::new(RwLock::new(Vec::new())),
+            pending: Arc::new(RwLock::new(HashMap::new())),
+            transports: Arc::new(RwLock::new(HashMap::new())),
+            heartbeat_config: Arc::new(RwLock::new(HeartbeatConfig::default())),
+            heartbeat_misses: Arc::new(RwLock::new(HashMap::new())),
+            discovery_handlers: Arc::new(RwLock::new(HashMap::new())),
+            discovery_config: Arc::new(RwLock::new(DiscoveryConfig::default())),
+            llm_providers: Arc::new(RwLock::new(HashMap::new())),
+            supervisor: Arc::new(Supervisor::new(OneForOnePolicy::default())),
+            shutdown_tx,
+            started: Arc::new(RwLock::new(false)),
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            port: 8080,
+        };
+
+        // Initialize default services
+        coordinator.initialize_default_services().await?;
+
+        log::info!("MCP Coordinator initialized successfully");
+        Ok(coordinator)
+    }
+
+    /// Brings the coordinator's background loops (heartbeat monitoring,
+    /// discovery reconciliation) up. Safe to call only once per instance;
+    /// a second call logs a warning and does nothing. Pair with
+    /// `shutdown()` for deterministic teardown.
+    pub async fn start(&self) -> Result<()> {
+        {
+            let mut started = self.started.write().await;
+            if *started {
+                log::warn!("MCPCoordinator::start called more than once; ignoring");
+                return Ok(());
+            }
+            *started = true;
+        }
+
+        // Spawned for the coordinator's whole lifetime (until `shutdown`);
+        // reads `heartbeat_config` fresh on every tick, so
+        // `set_heartbeat_config` takes effect without restarting it.
+        let monitor = self.clone();
+        let mut monitor_shutdown = self.shutdown_tx.subscribe();
+        tokio::spawn(async move {
+            monitor.run_heartbeat_monitor(&mut monitor_shutdown).await;
+        });
+
+        // Likewise for discovery reconciliation: a no-op tick until
+        // handlers are registered via `register_discovery_handler`.
+        let reconciler = self.clone();
+        let mut reconciler_shutdown = self.shutdown_tx.subscribe();
+        tokio::spawn(async move {
+            reconciler.run_discovery_reconciliation_loop(&mut reconciler_shutdown).await;
+        });
+
+        log::info!("MCP Coordinator started");
+        Ok(())
+    }
+
+    /// Signals every background loop started by `start()` to stop, then
+    /// gracefully tears down each currently connected service via
+    /// `deregister_service` so nothing is left dangling. Idempotent: a
+    /// coordinator that was never started, or already shut down, just has
+    /// no connections left to tear down.
+    pub async fn shutdown(&self) -> Result<()> {
+        log::info!("Shutting down MCP Coordinator...");
+        let _ = self.shutdown_tx.send(true);
+
+        let service_ids: Vec<String> = self.transports.read().await.keys().cloned().collect();
+        for service_id in service_ids {
+            self.transports.write().await.remove(&service_id);
+            self.deregister_service(&service_id).await?;
+        }
+
+        *self.started.write().await = false;
+        log::info!("MCP Coordinator shut down");
+        Ok(())
+    }
+
+    /// Reconfigures the background heartbeat monitor. Takes effect from its
+    /// next tick, since the running task reads this value live.
+    pub async fn set_heartbeat_config(&self, interval: Duration, miss_threshold: u32, stale_timeout: Duration) {
+        let mut config = self.heartbeat_config.write().await;
+        *config = HeartbeatConfig { interval, miss_threshold, stale_timeout };
+    }
+
+    /// Reconfigures the background discovery reconciliation loop. Takes
+    /// effect from its next tick.
+    pub async fn set_discovery_config(&self, interval: Duration) {
+        let mut config = self.discovery_config.write().await;
+        *config = DiscoveryConfig { interval };
+    }
+
+    /// Registers an `LlmProvider` under `name`, so any tool whose
+    /// `schema.provider` matches dispatches to it from `execute_tool`.
+    /// Registering under a name already in use replaces the previous
+    /// provider.
+    pub async fn register_provider(&self, name: impl Into<String>, provider: Box<dyn LlmProvider>) {
+        let mut providers = self.llm_providers.write().await;
+        providers.insert(name.into(), provider);
+    }
+
+    /// Registers a `DiscoveryHandler` so it's fanned out across by
+    /// `discover_tools` and the background reconciliation loop, keyed by its
+    /// `DiscoveryHandler::id`. Registering under an id already in use
+    /// replaces the previous handler.
+    pub async fn register_discovery_handler(&self, handler: Arc<dyn DiscoveryHandler>) {
+        let mut handlers = self.discovery_handlers.write().await;
+        handlers.insert(handler.id().to_string(), handler);
+    }
+
+    /// Polls every registered `DiscoveryHandler` once per
+    /// `discovery_config.interval`, reconciling their results into
+    /// `services`/`tools` via `discover_tools`, until `shutdown` fires.
+    async fn run_discovery_reconciliation_loop(&self, shutdown: &mut watch::Receiver<bool>) {
+        loop {
+            let interval = self.discovery_config.read().await.interval;
+            tokio::select! {
+                _ = tokio::time::sleep(interval) => {}
+                _ = shutdown.changed() => {
+                    log::info!("Discovery reconciliation loop shutting down");
+                    return;
+                }
+            }
+
+            if let Err(e) = self.discover_tools().await {
+                log::warn!("Discovery reconciliation failed: {}", e);
+            }
+        }
+    }
+
+    /// Drains `Registration`/`Deregistration` messages off the shared queue
+    /// and applies them via `register_service`/`deregister_service` — the
+    /// "registration endpoint" services and discovery handlers use to
+    /// announce themselves at runtime over `MCPMessageType::Registration`,
+    /// rather than only being known in advance. Other message types are
+    /// left in the queue untouched. Returns how many were processed.
+    pub async fn process_registration_messages(&self) -> Result<usize> {
+        let mut queue = self.message_queue.write().await;
+        let mut remaining = Vec::with_capacity(queue.len());
+        let mut registrations = Vec::new();
+        let mut deregistrations = Vec::new();
+
+        for message in queue.drain(..) {
+            match message.message_type {
+                MCPMessageType::Registration => registrations.push(message),
+                MCPMessageType::Deregistration => deregistrations.push(message),
+                _ => remaining.push(message),
+            }
+        }
+        *queue = remaining;
+        drop(queue);
+
+        let mut processed = 0;
+
+        for message in registrations {
+            match serde_json::from_value::<MCPService>(message.payload) {
+                Ok(service) => {
+                    self.register_service(service).await?;
+                    processed += 1;
+                }
+                Err(e) => log::warn!("Malformed service registration from '{}': {}", message.source, e),
+            }
+        }
+
+        for message in deregistrations {
+            match message.payload.get("service_id").and_then(|v| v.as_str()) {
+                Some(service_id) => {
+                    self.deregister_service(service_id).await?;
+                    processed += 1;
+                }
+                None => log::warn!("Malformed service deregistration from '{}'", message.source),
+            }
+        }
+
+        Ok(processed)
+    }
+
+    /// Pings every registered service once per `heartbeat_config.interval`,
+    /// until `shutdown` fires. A reply within the interval marks the
+    /// service `Online`; a miss bumps it toward `Connecting` then `Error`;
+    /// once a service goes `stale_timeout` past its `last_heartbeat` it's
+    /// handed to the `Supervisor` for a restart attempt (or deregistration,
+    /// once its retries are exhausted), mirroring the registration/liveness
+    /// reconciliation loop device discovery agents like Akri run against
+    /// their node leases.
+    async fn run_heartbeat_monitor(&self, shutdown: &mut watch::Receiver<bool>) {
+        loop {
+            let config = *self.heartbeat_config.read().await;
+            tokio::select! {
+                _ = tokio::time::sleep(config.interval) => {}
+                _ = shutdown.changed() => {
+                    log::info!("Heartbeat monitor shutting down");
+                    return;
+                }
+            }
+
+            // Each check awaits its own reply for up to `config.interval`,
+            // so these must run concurrently rather than one after another
+            // — a single slow/wedged service would otherwise stall every
+            // other service's liveness check for the same tick.
+            let service_ids: Vec<String> = self.services.read().await.keys().cloned().collect();
+            for service_id in service_ids {
+                let coordinator = self.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = coordinator.check_service_heartbeat(&service_id, &config).await {
+                        log::warn!("Heartbeat check for service '{}' failed: {}", service_id, e);
+                    }
+                });
+            }
+        }
+    }
+
+    async fn check_service_heartbeat(&self, service_id: &str, config: &HeartbeatConfig) -> Result<()> {
+        if self.is_simulated_service(service_id).await {
+            return self.mark_service_reachable(service_id).await;
+        }
+
+        let message = MCPMessage {
+            id: Uuid::new_v4().to_string(),
+            message_type: MCPMessageType::Heartbeat,
+            source: "coordinator".to_string(),
+            target: service_id.to_string(),
+            payload: serde_json::json!({}),
+            timestamp: Utc::now(),
+            correlation_id: None,
+        };
+
+        let correlation_id = message.id.clone();
+        let (tx, rx) = oneshot::channel();
+        self.pending.write().await.insert(correlation_id.clone(), tx);
+
+        let replied = match self.send_message(message).await {
+            Ok(()) => tokio::time::timeout(config.interval, rx).await.map(|r| r.is_ok()).unwrap_or(false),
+            Err(_) => false,
+        };
+        self.pending.write().await.remove(&correlation_id);
+
+        if replied {
+            self.mark_service_reachable(service_id).await
+        } else {
+            self.mark_service_missed(service_id, config).await
+        }
+    }
+
+    /// Whether `service_id` is one of the built-in default services
+    /// registered by `initialize_default_services` (`metadata["simulated"]
+    /// == "true"`) rather than something `connect_transport` has actually
+    /// dialed. Used to keep those defaults responsive instead of timing out
+    /// on every heartbeat and tool call against an endpoint nothing is
+    /// listening on.
+    async fn is_simulated_service(&self, service_id: &str) -> bool {
+        self.services.read().await
+            .get(service_id)
+            .map(|service| service.metadata.get("simulated").map(String::as_str) == Some("true"))
+            .unwrap_or(false)
+    }
+
+    async fn mark_service_reachable(&self, service_id: &str) -> Result<()> {
+        self.heartbeat_misses.write().await.remove(service_id);
+
+        let previous_status = {
+            let mut services = self.services.write().await;
+            services.get_mut(service_id).map(|service| {
+                let previous = service.status.clone();
+                service.status = MCPServiceStatus::Online;
+                service.last_heartbeat = Utc::now();
+                previous
+            })
+        };
+
+        if let Some(previous) = previous_status {
+            if !matches!(previous, MCPServiceStatus::Online) {
+                self.emit_status_event(service_id, &MCPServiceStatus::Online).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn mark_service_missed(&self, service_id: &str, config: &HeartbeatConfig) -> Result<()> {
+        let misses = {
+            let mut heartbeat_misses = self.heartbeat_misses.write().await;
+            let count = heartbeat_misses.entry(service_id.to_string()).or_insert(0);
+            *count += 1;
+            *count
+        };
+
+        let stale = {
+            let services = self.services.read().await;
+            services.get(service_id)
+                .map(|service| {
+                    Utc::now().signed_duration_since(service.last_heartbeat)
+                        .to_std()
+                        .map(|age| age >= config.stale_timeout)
+                        .unwrap_or(false)
+                })
+                .unwrap_or(false)
+        };
+
+        if stale {
+            log::warn!("Service '{}' exceeded its stale window ({:?}); handing off to the supervisor", service_id, config.stale_timeout);
+            self.heartbeat_misses.write().await.remove(service_id);
+
+            // `handle_failure` sleeps for its backoff and then awaits a
+            // reconnect attempt — both can take far longer than one
+            // heartbeat tick, so it's spawned as its own task instead of
+            // being awaited here, where it would stall every other
+            // service's heartbeat check behind it.
+            let supervisor = self.supervisor.clone();
+            let coordinator = self.clone();
+            let service_id = service_id.to_string();
+            tokio::spawn(async move {
+                if let Err(e) = supervisor.handle_failure(&coordinator, &service_id).await {
+                    log::warn!("Supervisor failed to recover service '{}': {}", service_id, e);
+                }
+            });
+            return Ok(());
+        }
+
+        let new_status = if misses >= config.miss_threshold {
+            MCPServiceStatus::Error
+        } else {
+            MCPServiceStatus::Connecting
+        };
+
+        let previous_status = {
+            let mut services = self.services.write().await;
+            services.get_mut(service_id).map(|service| {
+                let previous = service.status.clone();
+                service.status = new_status.clone();
+                previous
+            })
+        };
+
+        if let Some(previous) = previous_status {
+            if std::mem::discriminant(&previous) != std::mem::discriminant(&new_status) {
+                self.emit_status_event(service_id, &new_status).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Pushes an `Event` message reporting `service_id`'s new status, so
+    /// consumers draining `receive_messages` can react to it going offline.
+    async fn emit_status_event(&self, service_id: &str, status: &MCPServiceStatus) -> Result<()> {
+        let event = MCPMessage {
+            id: Uuid::new_v4().to_string(),
+            message_type: MCPMessageType::Event,
+            source: "coordinator".to_string(),
+            target: service_id.to_string(),
+            payload: serde_json::json!({
+                "event": "status_changed",
+                "service_id": service_id,
+                "status": status,
+            }),
+            timestamp: Utc::now(),
+            correlation_id: None,
+        };
+
+        self.message_queue.write().await.push(event);
+        Ok(())
+    }
+
+    /// Overrides how long `execute_tool` waits for a `Response` before
+    /// timing out.
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+    
+    async fn initialize_default_services(&self) -> Result<()> {
+        log::info!("Initializing default MCP services...");
+        
+        let mut services = self.services.write().await;
+        let mut tools = self.tools.write().await;
+        
+        // Stagewise MCP Service. No transport is ever dialed for its
+        // `mcp://` endpoint (there's nothing listening on it), so it's
+        // marked `simulated` rather than left to silently time out on
+        // every heartbeat and tool call — see `is_simulated_service`.
+        let stagewise_service = MCPService {
+            id: "stagewise_mcp".to_string(),
+            name: "Stagewise Visual Programming".to_string(),
+            endpoint: "mcp://localhost:8081/stagewise".to_string(),
+            status: MCPServiceStatus::Online,
+            capabilities: vec![
+                "visual_programming".to_string(),
+                "workflow_automation".to_string(),
+                "ui_generation".to_string(),
+            ],
+            last_heartbeat: Utc::now(),
+            metadata: HashMap::from([("simulated".to_string(), "true".to_string())]),
+        };
+        
+        services.insert(stagewise_service.id.clone(), stagewise_service.clone());
+        
+        // Stagewise Tools
+        tools.insert("visual_editor".to_string(), MCPTool {
+            id: "visual_editor".to_string(),
+            name: "Visual Editor".to_string(),
+            description: "Drag-and-drop visual programming interface".to_string(),
+            version: "1.0.0".to_string(),
+            service_id: stagewise_service.id.clone(),
+            capabilities: vec!["create_workflow".to_string(), "edit_workflow".to_string()],
+            schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "workflow_id": {"type": "string"},
+                    "nodes": {"type": "array"},
+                    "connections": {"type": "array"}
+                }
+            }),
+        });
+        
+        // AG-UI MCP Service — same simulated-only caveat as stagewise_mcp.
+        let ag_ui_service = MCPService {
+            id: "ag_ui_mcp".to_string(),
+            name: "AG-UI Component Generator".to_string(),
+            endpoint: "mcp://localhost:8082/ag_ui".to_string(),
+            status: MCPServiceStatus::Online,
+            capabilities: vec![
+                "ui_generation".to_string(),
+                "component_creation".to_string(),
+                "interaction_design".to_string(),
+            ],
+            last_heartbeat: Utc::now(),
+            metadata: HashMap::from([("simulated".to_string(), "true".to_string())]),
+        };
+        
+        services.insert(ag_ui_service.id.clone(), ag_ui_service.clone());
+        
+        // AG-UI Tools
+        tools.insert("component_generator".to_string(), MCPTool {
+            id: "component_generator".to_string(),
+            name: "Component Generator".to_string(),
+            description: "Generate UI components from natural language descriptions".to_string(),
+            version: "1.0.0".to_string(),
+            service_id: ag_ui_service.id.clone(),
+            capabilities: vec!["generate_component".to_string(), "customize_component".to_string()],
+            schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "description": {"type": "string"},
+                    "component_type": {"type": "string"},
+                    "style_preferences": {"type": "object"}
+                }
+            }),
+        });
+        
+        // Agent Zero MCP Service — same simulated-only caveat as stagewise_mcp.
+        let agent_zero_service = MCPService {
+            id: "agent_zero_mcp".to_string(),
+            name: "Agent Zero Organic Intelligence".to_string(),
+            endpoint: "mcp://localhost:8083/agent_zero".to_string(),
+            status: MCPServiceStatus::Online,
+            capabilities: vec![
+                "organic_learning".to_string(),
+                "adaptive_behavior".to_string(),
+                "autonomous_operation".to_string(),
+            ],
+            last_heartbeat: Utc::now(),
+            metadata: HashMap::from([("simulated".to_string(), "true".to_string())]),
+        };
+        
+        services.insert(agent_zero_service.id.clone(), agent_zero_service.clone());
+        
+        // Agent Zero Tools
+        tools.insert("organic_agent".to_string(), MCPTool {
+            id: "organic_agent".to_string(),
+            name: "Organic Agent".to_string(),
+            description: "Self-learning AI agent with adaptive capabilities".to_string(),
+            version: "1.0.0".to_string(),
+            service_id: agent_zero_service.id.clone(),
+            capabilities: vec!["learn".to_string(), "adapt".to_string(), "execute".to_string()],
+            schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "task": {"type": "string"},
+                    "context": {"type": "object"},
+                    "learning_mode": {"type": "boolean"}
+                }
+            }),
+        });
+        
+        log::info!("Initialized {} services and {} tools", services.len(), tools.len());
+        Ok(())
+    }
+    
+    /// Sets `service_id`'s status directly, emitting a status-change event
+    /// when it actually changes. Used by the `Supervisor` to reflect
+    /// reconnect attempts/successes without going through the heartbeat
+    /// miss-counting path.
+    pub async fn set_service_status(&self, service_id: &str, status: MCPServiceStatus) -> Result<()> {
+        let previous_status = {
+            let mut services = self.services.write().await;
+            services.get_mut(service_id).map(|service| {
+                let previous = service.status.clone();
+                service.status = status.clone();
+                previous
+            })
+        };
+
+        if let Some(previous) = previous_status {
+            if std::mem::discriminant(&previous) != std::mem::discriminant(&status) {
+                self.emit_status_event(service_id, &status).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn register_service(&self, service: MCPService) -> Result<()> {
+        log::info!("Registering MCP service: {}", service.name);
+        
+        let mut services = self.services.write().await;
+        services.insert(service.id.clone(), service);
+        
+        Ok(())
+    }
+    
+    pub async fn deregister_service(&self, service_id: &str) -> Result<()> {
+        log::info!("Deregistering MCP service: {}", service_id);
+        
+        let mut services = self.services.write().await;
+        services.remove(service_id);
+        
+        // Remove associated tools
+        let mut tools = self.tools.write().await;
+        tools.retain(|_, tool| tool.service_id != service_id);
+        
+        Ok(())
+    }
+    
+    pub async fn get_services(&self) -> Result<Vec<MCPService>> {
+        let services = self.services.read().await;
+        Ok(services.values().cloned().collect())
+    }
+    
+    pub async fn get_tools(&self) -> Result<Vec<MCPTool>> {
+        let tools = self.tools.read().await;
+        Ok(tools.values().cloned().collect())
+    }
+    
+    /// Fans out across every registered `DiscoveryHandler`, merging what
+    /// they report into `services`/`tools`: new services/tools are added,
+    /// and any service in `services` that no handler reports (the built-in
+    /// defaults and runtime-registered services included) is marked
+    /// `Offline` rather than removed outright. A coordinator with no
+    /// registered discovery handlers (the common case until one is
+    /// registered via `register_discovery_handler`) leaves `services`/
+    /// `tools` untouched, so the built-in defaults from
+    /// `initialize_default_services` survive until discovery is configured.
+    pub async fn discover_tools(&self) -> Result<Vec<String>> {
+        log::info!("Discovering MCP tools...");
+
+        let handlers: Vec<Arc<dyn DiscoveryHandler>> =
+            self.discovery_handlers.read().await.values().cloned().collect();
+
+        if !handlers.is_empty() {
+            let mut discovered_services = HashMap::new();
+            let mut discovered_tools = Vec::new();
+
+            for handler in &handlers {
+                match handler.discover().await {
+                    Ok(services) => {
+                        for service in services {
+                            discovered_services.insert(service.id.clone(), service);
+                        }
+                    }
+                    Err(e) => log::warn!("Discovery handler '{}' failed: {}", handler.id(), e),
+                }
+
+                match handler.discover_tools().await {
+                    Ok(tools) => discovered_tools.extend(tools),
+                    Err(e) => log::warn!("Discovery handler '{}' failed to list tools: {}", handler.id(), e),
+                }
+            }
+
+            {
+                let mut services = self.services.write().await;
+                for (id, service) in discovered_services.iter() {
+                    services.insert(id.clone(), service.clone());
+                }
+                for (id, service) in services.iter_mut() {
+                    if !discovered_services.contains_key(id) && !matches!(service.status, MCPServiceStatus::Offline) {
+                        log::info!("Service '{}' no longer reported by any discovery handler, marking offline", id);
+                        service.status = MCPServiceStatus::Offline;
+                    }
+                }
+            }
+
+            {
+                let mut tools = self.tools.write().await;
+                for tool in discovered_tools {
+                    tools.insert(tool.id.clone(), tool);
+                }
+            }
+        }
+
+        let tools = self.tools.read().await;
+        let tool_names: Vec<String> = tools.values()
+            .map(|tool| format!("{} ({})", tool.name, tool.service_id))
+            .collect();
+
+        log::info!("Discovered {} tools", tool_names.len());
+        Ok(tool_names)
+    }
+    
+    /// Dials (if not already connected) and stores the `Transport` for
+    /// `service_id`'s endpoint, so subsequent `send_message` calls targeting
+    /// it reuse the same connection instead of dialing again.
+    pub async fn connect_transport(&self, service_id: &str) -> Result<()> {
+        let service = {
+            let services = self.services.read().await;
+            services.get(service_id).cloned()
+                .ok_or_else(|| anyhow!("Service '{}' not found", service_id))?
+        };
+
+        let transport = mcp_transport::connect(&service).await?;
+
+        let mut transports = self.transports.write().await;
+        transports.insert(service_id.to_string(), transport);
+        Ok(())
+    }
+
+    pub async fn send_message(&self, message: MCPMessage) -> Result<()> {
+        log::info!("Sending MCP message: {} -> {}", message.source, message.target);
+
+        let transports = self.transports.read().await;
+        if let Some(transport) = transports.get(&message.target) {
+            return transport.send(&message).await;
+        }
+        drop(transports);
+
+        // No dialed transport for this target (e.g. a built-in simulated
+        // service with no real endpoint) — fall back to the shared queue.
+        let mut queue = self.message_queue.write().await;
+        queue.push(message);
+
+        Ok(())
+    }
+    
+    pub async fn receive_messages(&self) -> Result<Vec<MCPMessage>> {
+        let mut queue = self.message_queue.write().await;
+        let messages = queue.drain(..).collect();
+        Ok(messages)
+    }
+    
+    pub async fn health_check(&self) -> Result<HashMap<String, MCPServiceStatus>> {
+        log::info!("Performing health check on MCP services...");
+        
+        let services = self.services.read().await;
+        let mut health_status = HashMap::new();
+        
+        for (id, service) in services.iter() {
+            // In a real implementation, this would ping the service
+            health_status.insert(id.clone(), service.status.clone());
+        }
+        
+        Ok(health_status)
+    }
+    
+    /// Dispatches `tool_id`. A tool whose `schema.provider` names an
+    /// `LlmProvider` is forwarded straight to it via `execute_llm_tool`
+    /// (an error if no provider is registered under that name); a tool on a
+    /// simulated service gets `execute_simulated_tool`'s instant reply;
+    /// every other tool goes through the usual real
+    /// request/response round trip: sends a `Request` message, registers a
+    /// `oneshot` under its id, and awaits the matching `Response` the
+    /// transport layer delivers via `complete_message`, up to
+    /// `request_timeout`.
+    pub async fn execute_tool(&self, tool_id: &str, parameters: serde_json::Value) -> Result<serde_json::Value> {
+        log::info!("Executing tool: {}", tool_id);
+
+        let tools = self.tools.read().await;
+        let tool = tools.get(tool_id)
+            .ok_or_else(|| anyhow!("Tool '{}' not found", tool_id))?
+            .clone();
+        drop(tools);
+
+        if let Some(provider_name) = tool.schema.get("provider").and_then(|v| v.as_str()) {
+            let provider_name = provider_name.to_string();
+            return self.execute_llm_tool(&tool, &provider_name, parameters).await;
+        }
+
+        if self.is_simulated_service(&tool.service_id).await {
+            return self.execute_simulated_tool(&tool, parameters).await;
+        }
+
+        let message = MCPMessage {
+            id: Uuid::new_v4().to_string(),
+            message_type: MCPMessageType::Request,
+            source: "coordinator".to_string(),
+            target: tool.service_id.clone(),
+            payload: serde_json::json!({
+                "tool_id": tool_id,
+                "parameters": parameters
+            }),
+            timestamp: Utc::now(),
+            correlation_id: None,
+        };
+
+        let correlation_id = message.id.clone();
+        let (tx, rx) = oneshot::channel();
+        self.pending.write().await.insert(correlation_id.clone(), tx);
+
+        if let Err(e) = self.send_message(message).await {
+            self.pending.write().await.remove(&correlation_id);
+            return Err(e);
+        }
+
+        match tokio::time::timeout(self.request_timeout, rx).await {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(_)) => {
+                self.pending.write().await.remove(&correlation_id);
+                Err(anyhow!("Tool '{}' response channel closed before replying", tool_id))
+            }
+            Err(_) => {
+                self.pending.write().await.remove(&correlation_id);
+                Err(anyhow!(
+                    "Tool '{}' timed out waiting for a response after {:?}",
+                    tool_id,
+                    self.request_timeout
+                ))
+            }
+        }
+    }
+
+    /// Answers a tool call against a `simulated` service (see
+    /// `is_simulated_service`) with an instant synthesized success instead
+    /// of sending a `Request` no transport is dialed to deliver — restores
+    /// the default `visual_editor`/`component_generator`/`organic_agent`
+    /// tools' pre-transport behavior of an immediate (fake) reply rather
+    /// than a guaranteed `request_timeout` wait followed by failure.
+    async fn execute_simulated_tool(&self, tool: &MCPTool, parameters: serde_json::Value) -> Result<serde_json::Value> {
+        Ok(serde_json::json!({
+            "tool_id": tool.id,
+            "status": "success",
+            "simulated": true,
+            "parameters": parameters,
+        }))
+    }
+
+    /// Dispatches an LLM-backed tool straight to the registered
+    /// `LlmProvider` named `provider_name`, instead of the
+    /// request/response message round trip `execute_tool` otherwise uses.
+    /// `tool.schema.request` is the provider-specific request body
+    /// template; the caller's `parameters` are merged into it verbatim
+    /// (filling in whatever fields they name) rather than reshaped into a
+    /// lowest-common-denominator format, so newly released models work
+    /// without a code change here. Token usage is surfaced alongside the
+    /// provider's raw result.
+    async fn execute_llm_tool(
+        &self,
+        tool: &MCPTool,
+        provider_name: &str,
+        parameters: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let providers = self.llm_providers.read().await;
+        let provider = providers.get(provider_name)
+            .ok_or_else(|| anyhow!("LLM provider '{}' is not registered", provider_name))?;
+
+        let mut request_body = tool.schema.get("request").cloned().unwrap_or(serde_json::Value::Null);
+        match (request_body.as_object_mut(), parameters.as_object()) {
+            (Some(body), Some(params)) => body.extend(params.clone()),
+            _ => request_body = parameters,
+        }
+
+        let completion = provider.complete(request_body).await?;
+
+        Ok(serde_json::json!({
+            "provider": provider_name,
+            "result": completion.raw,
+            "usage": completion.usage,
+        }))
+    }
+
+    /// Dispatches one or more JSON-RPC 2.0 request frames — `raw` is either
+    /// a single frame object or a JSON array of them (the spec's batch
+    /// convention) — as MCP `Request`s targeted at `target`. Each frame is
+    /// correlated back by its own JSON-RPC `id` through the same
+    /// `pending`/`oneshot` mechanism as `execute_tool`, so real MCP clients
+    /// can submit a batch in one round trip instead of only this
+    /// coordinator's own internal format.
+    pub async fn send_jsonrpc_batch(&self, target: &str, raw: &str) -> Result<Vec<serde_json::Value>> {
+        let requests = jsonrpc::parse_requests(raw)?;
+
+        let mut in_flight = Vec::with_capacity(requests.len());
+        for request in &requests {
+            let outcome = match jsonrpc::from_request(request, "coordinator", target) {
+                Ok(message) => {
+                    let correlation_id = message.id.clone();
+                    let (tx, rx) = oneshot::channel();
+                    self.pending.write().await.insert(correlation_id.clone(), tx);
+
+                    match self.send_message(message).await {
+                        Ok(()) => Ok((correlation_id, rx)),
+                        Err(e) => {
+                            self.pending.write().await.remove(&correlation_id);
+                            Err(e)
+                        }
+                    }
+                }
+                Err(e) => Err(e),
+            };
+
+            in_flight.push((request.id.clone(), outcome));
+        }
+
+        let mut responses = Vec::with_capacity(in_flight.len());
+        for (request_id, outcome) in in_flight {
+            let response = match outcome {
+                Err(e) => jsonrpc::error_response(request_id, jsonrpc::ERROR_INTERNAL, e.to_string()),
+                Ok((correlation_id, rx)) => match tokio::time::timeout(self.request_timeout, rx).await {
+                    Ok(Ok(result)) => jsonrpc::success_response(request_id, result),
+                    Ok(Err(_)) => {
+                        self.pending.write().await.remove(&correlation_id);
+                        jsonrpc::error_response(
+                            request_id,
+                            jsonrpc::ERROR_INTERNAL,
+                            "response channel closed before replying",
+                        )
+                    }
+                    Err(_) => {
+                        self.pending.write().await.remove(&correlation_id);
+                        jsonrpc::error_response(
+                            request_id,
+                            jsonrpc::ERROR_INTERNAL,
+                            format!("timed out after {:?}", self.request_timeout),
+                        )
+                    }
+                },
+            };
+
+            responses.push(
+                serde_json::to_value(&response).expect("JsonRpcResponse is always serializable"),
+            );
+        }
+
+        Ok(responses)
+    }
+
+    /// Fires the `oneshot` registered for `correlation_id` with `result`,
+    /// resuming whichever `execute_tool` call is awaiting it. Called by the
+    /// transport layer when a `Response` message arrives. A correlation id
+    /// with no pending sender (already timed out, or duplicated) is logged
+    /// and otherwise ignored.
+    pub async fn complete_message(&self, correlation_id: &str, result: serde_json::Value) {
+        match self.pending.write().await.remove(correlation_id) {
+            Some(sender) => {
+                if sender.send(result).is_err() {
+                    log::warn!("No caller awaiting response for correlation id '{}'", correlation_id);
+                }
+            }
+            None => {
+                log::warn!("Received response for unknown correlation id '{}'", correlation_id);
+            }
+        }
+    }
+    
+    pub async fn get_service_capabilities(&self, service_id: &str) -> Result<Vec<String>> {
+        let services = self.services.read().await;
+        let service = services.get(service_id)
+            .ok_or_else(|| anyhow!("Service '{}' not found", service_id))?;
+        
+        Ok(service.capabilities.clone())
+    }
+    
+    pub async fn get_tool_schema(&self, tool_id: &str) -> Result<serde_json::Value> {
+        let tools = self.tools.read().await;
+        let tool = tools.get(tool_id)
+            .ok_or_else(|| anyhow!("Tool '{}' not found", tool_id))?;
+        
+        Ok(tool.schema.clone())
+    }
+}
+
@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::Deserialize;
+
+use crate::mcp::{MCPService, MCPServiceStatus, MCPTool};
+
+/// A source of `MCPService`s that can be polled for what's currently
+/// available, modeled on Akri's discovery-handler registration: handlers are
+/// registered with the coordinator at runtime and fanned out across on a
+/// schedule rather than the service list being baked into the binary.
+#[async_trait]
+pub trait DiscoveryHandler: Send + Sync {
+    /// A stable id for this handler, used for logging and to distinguish it
+    /// from others in the registry.
+    fn id(&self) -> &str;
+
+    /// Returns the services this handler currently sees. Called on every
+    /// reconciliation tick; an error here just skips this handler for that
+    /// tick rather than failing the whole reconciliation.
+    async fn discover(&self) -> Result<Vec<MCPService>>;
+
+    /// Tools this handler additionally knows about, alongside the services
+    /// from `discover`. Defaults to none — most handlers only discover
+    /// services; `StaticConfigHandler` overrides this to also supply the
+    /// tools loaded from its config file.
+    async fn discover_tools(&self) -> Result<Vec<MCPTool>> {
+        Ok(Vec::new())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct StaticConfigManifest {
+    services: Vec<StaticServiceEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StaticServiceEntry {
+    id: String,
+    name: String,
+    endpoint: String,
+    #[serde(default)]
+    capabilities: Vec<String>,
+    #[serde(default)]
+    metadata: HashMap<String, String>,
+    #[serde(default)]
+    tools: Vec<StaticToolEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StaticToolEntry {
+    id: String,
+    name: String,
+    description: String,
+    #[serde(default = "default_tool_version")]
+    version: String,
+    #[serde(default)]
+    capabilities: Vec<String>,
+    #[serde(default)]
+    schema: serde_json::Value,
+}
+
+fn default_tool_version() -> String {
+    "1.0.0".to_string()
+}
+
+/// Built-in `DiscoveryHandler` that reads service and tool definitions from
+/// a JSON or TOML file (format inferred from its extension). The built-in
+/// defaults registered by `initialize_default_services` are still defined
+/// in code, not loaded through this handler.
+pub struct StaticConfigHandler {
+    id: String,
+    entries: Vec<StaticServiceEntry>,
+}
+
+impl StaticConfigHandler {
+    /// Loads `path` (`.json` or `.toml`) into a handler registered under
+    /// `id`.
+    pub fn load(id: impl Into<String>, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read discovery config {}: {}", path.display(), e))?;
+
+        let manifest: StaticConfigManifest = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => toml::from_str(&raw)
+                .map_err(|e| anyhow!("Failed to parse discovery config {}: {}", path.display(), e))?,
+            _ => serde_json::from_str(&raw)
+                .map_err(|e| anyhow!("Failed to parse discovery config {}: {}", path.display(), e))?,
+        };
+
+        Ok(Self { id: id.into(), entries: manifest.services })
+    }
+
+    /// Flattens every service's tool definitions into `MCPTool`s, so the
+    /// coordinator's reconciliation can populate its `tools` map alongside
+    /// the services this handler discovers.
+    pub fn tools(&self) -> Vec<MCPTool> {
+        self.entries
+            .iter()
+            .flat_map(|entry| {
+                entry.tools.iter().map(move |tool| MCPTool {
+                    id: tool.id.clone(),
+                    name: tool.name.clone(),
+                    description: tool.description.clone(),
+                    version: tool.version.clone(),
+                    service_id: entry.id.clone(),
+                    capabilities: tool.capabilities.clone(),
+                    schema: tool.schema.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl DiscoveryHandler for StaticConfigHandler {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    async fn discover(&self) -> Result<Vec<MCPService>> {
+        Ok(self
+            .entries
+            .iter()
+            .map(|entry| MCPService {
+                id: entry.id.clone(),
+                name: entry.name.clone(),
+                endpoint: entry.endpoint.clone(),
+                status: MCPServiceStatus::Online,
+                capabilities: entry.capabilities.clone(),
+                last_heartbeat: Utc::now(),
+                metadata: entry.metadata.clone(),
+            })
+            .collect())
+    }
+
+    async fn discover_tools(&self) -> Result<Vec<MCPTool>> {
+        Ok(self.tools())
+    }
+}
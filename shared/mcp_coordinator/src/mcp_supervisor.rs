@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use tokio::sync::RwLock;
+
+use crate::mcp::{MCPCoordinator, MCPServiceStatus};
+
+/// One-for-one restart policy: a failing service is retried on its own,
+/// independently of its siblings, with exponentially growing backoff
+/// between attempts, up to `max_retries`.
+#[derive(Debug, Clone, Copy)]
+pub struct OneForOnePolicy {
+    pub max_retries: u32,
+    pub base_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for OneForOnePolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            base_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+}
+
+impl OneForOnePolicy {
+    fn backoff_for(&self, attempt: u32) -> Duration {
+        let scaled = self.base_backoff.saturating_mul(1u32 << attempt.min(16));
+        scaled.min(self.max_backoff)
+    }
+}
+
+/// Supervises each connected service's transport as a restartable child,
+/// inspired by the overclock actor runtime's one-for-one supervision trees:
+/// a failure reconnects that one service with exponential backoff,
+/// transitioning its `MCPServiceStatus` as it goes, and escalates to
+/// `MCPCoordinator::deregister_service` once `OneForOnePolicy::max_retries`
+/// is exhausted — rather than a crashed connection just lingering.
+pub struct Supervisor {
+    policy: OneForOnePolicy,
+    attempts: Arc<RwLock<HashMap<String, u32>>>,
+}
+
+impl Supervisor {
+    pub fn new(policy: OneForOnePolicy) -> Self {
+        Self { policy, attempts: Arc::new(RwLock::new(HashMap::new())) }
+    }
+
+    /// Resets a service's retry count, so a later unrelated failure starts
+    /// counting from zero instead of inheriting an old streak.
+    async fn record_success(&self, service_id: &str) {
+        self.attempts.write().await.remove(service_id);
+    }
+
+    /// Reacts to `service_id` having gone unreachable: sleeps for this
+    /// attempt's backoff, then asks `coordinator` to reconnect its
+    /// transport. Deregisters the service once `max_retries` is exceeded.
+    pub async fn handle_failure(&self, coordinator: &MCPCoordinator, service_id: &str) -> Result<()> {
+        let attempt = {
+            let mut attempts = self.attempts.write().await;
+            let count = attempts.entry(service_id.to_string()).or_insert(0);
+            *count += 1;
+            *count
+        };
+
+        if attempt > self.policy.max_retries {
+            log::warn!(
+                "Service '{}' exceeded {} restart attempts, deregistering",
+                service_id, self.policy.max_retries
+            );
+            self.attempts.write().await.remove(service_id);
+            return coordinator.deregister_service(service_id).await;
+        }
+
+        let backoff = self.policy.backoff_for(attempt - 1);
+        log::info!(
+            "Restarting service '{}' (attempt {}/{}) after {:?}",
+            service_id, attempt, self.policy.max_retries, backoff
+        );
+        tokio::time::sleep(backoff).await;
+
+        match coordinator.connect_transport(service_id).await {
+            Ok(()) => {
+                self.record_success(service_id).await;
+                coordinator.set_service_status(service_id, MCPServiceStatus::Online).await
+            }
+            Err(e) => {
+                log::warn!("Restart attempt {} for service '{}' failed: {}", attempt, service_id, e);
+                coordinator.set_service_status(service_id, MCPServiceStatus::Error).await
+            }
+        }
+    }
+}
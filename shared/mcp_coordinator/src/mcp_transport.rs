@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, ReadHalf, WriteHalf};
+use tokio::net::TcpStream;
+use tokio::process::{Child, Command};
+use tokio::sync::Mutex;
+use tokio_rustls::{rustls, TlsConnector};
+use tokio_tungstenite::{connect_async, tungstenite::Message as WsMessage, MaybeTlsStream, WebSocketStream};
+
+use crate::mcp::{MCPMessage, MCPService};
+
+/// A bidirectional channel `MCPCoordinator` dials to reach one
+/// `MCPService`, selected by the service's endpoint scheme via `connect`.
+/// Each message is framed independently (newline-delimited JSON for the
+/// stream-based transports, one JSON text frame per WebSocket message).
+#[async_trait]
+pub trait Transport: Send + Sync {
+    async fn send(&self, msg: &MCPMessage) -> Result<()>;
+    async fn recv(&self) -> Result<MCPMessage>;
+}
+
+/// TLS settings parsed from an `MCPService`'s `metadata` (`tls_ca`,
+/// `tls_cert`, `tls_key`). A service with none of these set dials plaintext.
+#[derive(Debug, Clone, Default)]
+pub struct TlsEndpointConfig {
+    pub ca_path: Option<PathBuf>,
+    pub cert_path: Option<PathBuf>,
+    pub key_path: Option<PathBuf>,
+}
+
+impl TlsEndpointConfig {
+    pub fn from_metadata(metadata: &HashMap<String, String>) -> Self {
+        Self {
+            ca_path: metadata.get("tls_ca").map(PathBuf::from),
+            cert_path: metadata.get("tls_cert").map(PathBuf::from),
+            key_path: metadata.get("tls_key").map(PathBuf::from),
+        }
+    }
+
+    pub fn is_configured(&self) -> bool {
+        self.ca_path.is_some() || self.cert_path.is_some() || self.key_path.is_some()
+    }
+}
+
+/// Builds the right `Transport` for `service`'s endpoint: `stdio://<cmd>
+/// [args...]` spawns a child process and talks over its stdin/stdout;
+/// `ws://`/`wss://` dial a WebSocket; `mcp://host:port[/path]` dials
+/// TLS-over-TCP when `tls_*` metadata is present on the service, otherwise
+/// falls back to plaintext TCP.
+pub async fn connect(service: &MCPService) -> Result<Box<dyn Transport>> {
+    let endpoint = service.endpoint.as_str();
+
+    if let Some(command_line) = endpoint.strip_prefix("stdio://") {
+        let mut parts = command_line.split_whitespace();
+        let command = parts.next()
+            .ok_or_else(|| anyhow!("Empty stdio:// endpoint for service '{}'", service.id))?;
+        let args: Vec<String> = parts.map(String::from).collect();
+        return Ok(Box::new(StdioTransport::spawn(command, &args).await?));
+    }
+
+    if endpoint.starts_with("ws://") || endpoint.starts_with("wss://") {
+        return Ok(Box::new(WebSocketTransport::connect(endpoint).await?));
+    }
+
+    if let Some(rest) = endpoint.strip_prefix("mcp://") {
+        let (host, port) = parse_host_port(rest, service)?;
+        let tls_config = TlsEndpointConfig::from_metadata(&service.metadata);
+        if tls_config.is_configured() {
+            return Ok(Box::new(TlsTcpTransport::connect(&host, port, &tls_config).await?));
+        }
+        return Ok(Box::new(PlaintextTcpTransport::connect(&host, port).await?));
+    }
+
+    Err(anyhow!("Unsupported endpoint scheme for service '{}': '{}'", service.id, endpoint))
+}
+
+fn parse_host_port(rest: &str, service: &MCPService) -> Result<(String, u16)> {
+    let authority = rest.split('/').next().unwrap_or(rest);
+    let (host, port) = authority.split_once(':')
+        .ok_or_else(|| anyhow!("Endpoint for service '{}' is missing a port: '{}'", service.id, service.endpoint))?;
+    let port: u16 = port.parse()
+        .map_err(|e| anyhow!("Invalid port in endpoint for service '{}': {}", service.id, e))?;
+    Ok((host.to_string(), port))
+}
+
+/// Talks newline-delimited JSON over a spawned child process's stdin/stdout.
+pub struct StdioTransport {
+    _child: Mutex<Child>,
+    stdin: Mutex<tokio::process::ChildStdin>,
+    stdout: Mutex<BufReader<tokio::process::ChildStdout>>,
+}
+
+impl StdioTransport {
+    pub async fn spawn(command: &str, args: &[String]) -> Result<Self> {
+        let mut child = Command::new(command)
+            .args(args)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow!("Failed to spawn '{}': {}", command, e))?;
+
+        let stdin = child.stdin.take()
+            .ok_or_else(|| anyhow!("Child process '{}' has no stdin", command))?;
+        let stdout = child.stdout.take()
+            .ok_or_else(|| anyhow!("Child process '{}' has no stdout", command))?;
+
+        Ok(Self {
+            _child: Mutex::new(child),
+            stdin: Mutex::new(stdin),
+            stdout: Mutex::new(BufReader::new(stdout)),
+        })
+    }
+}
+
+#[async_trait]
+impl Transport for StdioTransport {
+    async fn send(&self, msg: &MCPMessage) -> Result<()> {
+        let line = serde_json::to_string(msg)
+            .map_err(|e| anyhow!("Failed to serialize message: {}", e))?;
+        let mut stdin = self.stdin.lock().await;
+        stdin.write_all(line.as_bytes()).await
+            .map_err(|e| anyhow!("Failed to write to child stdin: {}", e))?;
+        stdin.write_all(b"\n").await
+            .map_err(|e| anyhow!("Failed to write to child stdin: {}", e))
+    }
+
+    async fn recv(&self) -> Result<MCPMessage> {
+        let mut stdout = self.stdout.lock().await;
+        let mut line = String::new();
+        let bytes_read = stdout.read_line(&mut line).await
+            .map_err(|e| anyhow!("Failed to read from child stdout: {}", e))?;
+        if bytes_read == 0 {
+            return Err(anyhow!("Child process stdout closed"));
+        }
+        serde_json::from_str(line.trim_end())
+            .map_err(|e| anyhow!("Failed to parse message from child stdout: {}", e))
+    }
+}
+
+/// Sends/receives one JSON text frame per message over a WebSocket.
+pub struct WebSocketTransport {
+    stream: Mutex<WebSocketStream<MaybeTlsStream<TcpStream>>>,
+}
+
+impl WebSocketTransport {
+    pub async fn connect(url: &str) -> Result<Self> {
+        let (stream, _response) = connect_async(url).await
+            .map_err(|e| anyhow!("Failed to connect to '{}': {}", url, e))?;
+        Ok(Self { stream: Mutex::new(stream) })
+    }
+}
+
+#[async_trait]
+impl Transport for WebSocketTransport {
+    async fn send(&self, msg: &MCPMessage) -> Result<()> {
+        let text = serde_json::to_string(msg)
+            .map_err(|e| anyhow!("Failed to serialize message: {}", e))?;
+        self.stream.lock().await.send(WsMessage::Text(text)).await
+            .map_err(|e| anyhow!("Failed to send websocket message: {}", e))
+    }
+
+    async fn recv(&self) -> Result<MCPMessage> {
+        let mut stream = self.stream.lock().await;
+        loop {
+            match stream.next().await {
+                Some(Ok(WsMessage::Text(text))) => {
+                    return serde_json::from_str(&text)
+                        .map_err(|e| anyhow!("Failed to parse websocket message: {}", e));
+                }
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(anyhow!("Websocket error: {}", e)),
+                None => return Err(anyhow!("Websocket connection closed")),
+            }
+        }
+    }
+}
+
+/// Newline-delimited JSON over a plain TCP stream — the fallback for
+/// `mcp://` endpoints with no `tls_*` metadata.
+pub struct PlaintextTcpTransport {
+    reader: Mutex<BufReader<ReadHalf<TcpStream>>>,
+    writer: Mutex<WriteHalf<TcpStream>>,
+}
+
+impl PlaintextTcpTransport {
+    pub async fn connect(host: &str, port: u16) -> Result<Self> {
+        let stream = TcpStream::connect((host, port)).await
+            .map_err(|e| anyhow!("Failed to connect to '{}:{}': {}", host, port, e))?;
+        let (read_half, write_half) = tokio::io::split(stream);
+        Ok(Self {
+            reader: Mutex::new(BufReader::new(read_half)),
+            writer: Mutex::new(write_half),
+        })
+    }
+}
+
+#[async_trait]
+impl Transport for PlaintextTcpTransport {
+    async fn send(&self, msg: &MCPMessage) -> Result<()> {
+        let mut line = serde_json::to_string(msg)
+            .map_err(|e| anyhow!("Failed to serialize message: {}", e))?;
+        line.push('\n');
+        self.writer.lock().await.write_all(line.as_bytes()).await
+            .map_err(|e| anyhow!("Failed to write to TCP stream: {}", e))
+    }
+
+    async fn recv(&self) -> Result<MCPMessage> {
+        let mut reader = self.reader.lock().await;
+        let mut line = String::new();
+        let bytes_read = reader.read_line(&mut line).await
+            .map_err(|e| anyhow!("Failed to read from TCP stream: {}", e))?;
+        if bytes_read == 0 {
+            return Err(anyhow!("TCP connection closed"));
+        }
+        serde_json::from_str(line.trim_end())
+            .map_err(|e| anyhow!("Failed to parse message from TCP stream: {}", e))
+    }
+}
+
+/// Newline-delimited JSON over TLS-over-TCP, negotiated with `rustls`.
+/// Presents a client certificate when both `tls_cert` and `tls_key` are set
+/// on the service's metadata; otherwise authenticates the server only.
+pub struct TlsTcpTransport {
+    reader: Mutex<BufReader<ReadHalf<tokio_rustls::client::TlsStream<TcpStream>>>>,
+    writer: Mutex<WriteHalf<tokio_rustls::client::TlsStream<TcpStream>>>,
+}
+
+impl TlsTcpTransport {
+    pub async fn connect(host: &str, port: u16, tls_config: &TlsEndpointConfig) -> Result<Self> {
+        let mut root_store = rustls::RootCertStore::empty();
+        match &tls_config.ca_path {
+            Some(ca_path) => load_ca_cert(&mut root_store, ca_path)?,
+            None => root_store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned()),
+        }
+
+        let builder = rustls::ClientConfig::builder().with_root_certificates(root_store);
+
+        let config = match (&tls_config.cert_path, &tls_config.key_path) {
+            (Some(cert_path), Some(key_path)) => {
+                let certs = load_certs(cert_path)?;
+                let key = load_private_key(key_path)?;
+                builder.with_client_auth_cert(certs, key)
+                    .map_err(|e| anyhow!("Invalid client certificate/key for '{}:{}': {}", host, port, e))?
+            }
+            _ => builder.with_no_client_auth(),
+        };
+
+        let connector = TlsConnector::from(Arc::new(config));
+        let tcp = TcpStream::connect((host, port)).await
+            .map_err(|e| anyhow!("Failed to connect to '{}:{}': {}", host, port, e))?;
+        let server_name = rustls::pki_types::ServerName::try_from(host.to_string())
+            .map_err(|e| anyhow!("Invalid server name '{}': {}", host, e))?;
+        let tls_stream = connector.connect(server_name, tcp).await
+            .map_err(|e| anyhow!("TLS handshake with '{}:{}' failed: {}", host, port, e))?;
+
+        let (read_half, write_half) = tokio::io::split(tls_stream);
+        Ok(Self {
+            reader: Mutex::new(BufReader::new(read_half)),
+            writer: Mutex::new(write_half),
+        })
+    }
+}
+
+#[async_trait]
+impl Transport for TlsTcpTransport {
+    async fn send(&self, msg: &MCPMessage) -> Result<()> {
+        let mut line = serde_json::to_string(msg)
+            .map_err(|e| anyhow!("Failed to serialize message: {}", e))?;
+        line.push('\n');
+        self.writer.lock().await.write_all(line.as_bytes()).await
+            .map_err(|e| anyhow!("Failed to write to TLS stream: {}", e))
+    }
+
+    async fn recv(&self) -> Result<MCPMessage> {
+        let mut reader = self.reader.lock().await;
+        let mut line = String::new();
+        let bytes_read = reader.read_line(&mut line).await
+            .map_err(|e| anyhow!("Failed to read from TLS stream: {}", e))?;
+        if bytes_read == 0 {
+            return Err(anyhow!("TLS connection closed"));
+        }
+        serde_json::from_str(line.trim_end())
+            .map_err(|e| anyhow!("Failed to parse message from TLS stream: {}", e))
+    }
+}
+
+fn load_ca_cert(root_store: &mut rustls::RootCertStore, path: &Path) -> Result<()> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| anyhow!("Failed to open CA certificate '{}': {}", path.display(), e))?;
+    let mut reader = std::io::BufReader::new(file);
+    for cert in rustls_pemfile::certs(&mut reader) {
+        let cert = cert.map_err(|e| anyhow!("Failed to parse CA certificate '{}': {}", path.display(), e))?;
+        root_store.add(cert)
+            .map_err(|e| anyhow!("Failed to trust CA certificate '{}': {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+fn load_certs(path: &Path) -> Result<Vec<rustls::pki_types::CertificateDer<'static>>> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| anyhow!("Failed to open client certificate '{}': {}", path.display(), e))?;
+    let mut reader = std::io::BufReader::new(file);
+    rustls_pemfile::certs(&mut reader)
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| anyhow!("Failed to parse client certificate '{}': {}", path.display(), e))
+}
+
+fn load_private_key(path: &Path) -> Result<rustls::pki_types::PrivateKeyDer<'static>> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| anyhow!("Failed to open private key '{}': {}", path.display(), e))?;
+    let mut reader = std::io::BufReader::new(file);
+    rustls_pemfile::private_key(&mut reader)
+        .map_err(|e| anyhow!("Failed to parse private key '{}': {}", path.display(), e))?
+        .ok_or_else(|| anyhow!("No private key found in '{}'", path.display()))
+}
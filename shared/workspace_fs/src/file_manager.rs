@@ -0,0 +1,490 @@
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, anyhow};
+
+use crate::fs::{AtomicWriteOptions, Fs, FsMetadata, LocalFs};
+use crate::git_status::GitRepoCache;
+use crate::gitignore::GitIgnoreTree;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileInfo {
+    pub path: String,
+    pub name: String,
+    pub size: u64,
+    pub is_directory: bool,
+    pub extension: Option<String>,
+    pub modified: Option<String>,
+    /// `"added"`/`"modified"`/`"untracked"`/`"clean"` if `path` is inside a
+    /// git working tree, `None` otherwise (not a repo, or status lookup
+    /// failed).
+    pub git_status: Option<String>,
+}
+
+/// A file's dominant line-ending convention, detected on read and preserved
+/// on write so round-tripping a file without explicitly changing its
+/// convention doesn't produce a whole-file diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+}
+
+impl LineEnding {
+    fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::Crlf => "\r\n",
+        }
+    }
+
+    /// Counts `\r\n` pairs against total `\n`s and calls it `Crlf` if at
+    /// least half of them are part of a pair; defaults to `Lf` for
+    /// single-line or empty content.
+    fn detect(content: &str) -> Self {
+        let crlf_count = content.matches("\r\n").count();
+        let lf_count = content.matches('\n').count();
+        if lf_count > 0 && crlf_count * 2 >= lf_count {
+            LineEnding::Crlf
+        } else {
+            LineEnding::Lf
+        }
+    }
+}
+
+fn normalize_line_endings(content: &str, line_ending: LineEnding) -> String {
+    let lf = content.replace("\r\n", "\n");
+    match line_ending {
+        LineEnding::Lf => lf,
+        LineEnding::Crlf => lf.replace('\n', "\r\n"),
+    }
+}
+
+/// A file's content alongside the line ending it was read with, so a caller
+/// can write it back with `write_file(file_path, text, Some(line_ending))`
+/// and get the original convention back instead of whatever `text` contains.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileContent {
+    pub text: String,
+    pub line_ending: LineEnding,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryTree {
+    pub path: String,
+    pub name: String,
+    pub is_directory: bool,
+    pub children: Vec<DirectoryTree>,
+}
+
+/// Highest traversal depth `search_files` will descend to, matching the
+/// limit the old `walkdir`-based implementation used.
+const MAX_SEARCH_DEPTH: usize = 10;
+
+pub struct FileManager {
+    backend: Box<dyn Fs>,
+    git_cache: GitRepoCache,
+}
+
+impl FileManager {
+    pub fn new() -> Self {
+        Self { backend: Box::new(LocalFs), git_cache: GitRepoCache::new() }
+    }
+
+    /// Builds a `FileManager` over a custom backend, e.g. `MemoryFs` in
+    /// tests or a future remote/object-store `Fs` implementation.
+    pub fn with_backend(backend: Box<dyn Fs>) -> Self {
+        Self { backend, git_cache: GitRepoCache::new() }
+    }
+
+    async fn file_info_from(&self, path: &Path, metadata: &FsMetadata) -> FileInfo {
+        FileInfo {
+            path: path.to_string_lossy().to_string(),
+            name: path.file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            size: metadata.len,
+            is_directory: metadata.is_dir,
+            extension: path.extension()
+                .map(|ext| ext.to_string_lossy().to_string()),
+            modified: metadata.modified
+                .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|duration| duration.as_secs().to_string()),
+            git_status: self.git_cache.status_for(path).await,
+        }
+    }
+
+    /// The file's committed `HEAD` blob content, for rendering a
+    /// working-tree-vs-HEAD diff in the frontend.
+    pub async fn read_head_content(&self, file_path: &str) -> Result<String> {
+        log::info!("Reading HEAD content for: {}", file_path);
+        self.git_cache.head_content(Path::new(file_path)).await
+    }
+
+    /// Reads `file_path` and reports its dominant line ending alongside the
+    /// content, with the content itself normalized to `\n` for editing.
+    pub async fn read_file(&self, file_path: &str) -> Result<FileContent> {
+        log::info!("Reading file: {}", file_path);
+        let raw = self.backend.read(Path::new(file_path)).await?;
+        let line_ending = LineEnding::detect(&raw);
+        Ok(FileContent { text: normalize_line_endings(&raw, LineEnding::Lf), line_ending })
+    }
+
+    /// Writes `content` to `file_path` atomically (temp file + rename), so an
+    /// interrupted write never leaves a half-written file on disk.
+    /// `line_ending` is normalized to before writing; `None` preserves
+    /// whatever convention the file already has (or `Lf` for a new file).
+    /// Use `write_file_with_options` to also tune fsync/permission
+    /// preservation.
+    pub async fn write_file(&self, file_path: &str, content: &str, line_ending: Option<LineEnding>) -> Result<()> {
+        self.write_file_with_options(file_path, content, line_ending, AtomicWriteOptions::default()).await
+    }
+
+    pub async fn write_file_with_options(
+        &self,
+        file_path: &str,
+        content: &str,
+        line_ending: Option<LineEnding>,
+        options: AtomicWriteOptions,
+    ) -> Result<()> {
+        log::info!("Writing file: {}", file_path);
+
+        let line_ending = match line_ending {
+            Some(line_ending) => line_ending,
+            None => match self.backend.read(Path::new(file_path)).await {
+                Ok(existing) => LineEnding::detect(&existing),
+                Err(_) => LineEnding::Lf,
+            },
+        };
+
+        let normalized = normalize_line_endings(content, line_ending);
+        self.backend.write_atomic(Path::new(file_path), &normalized, &options).await
+    }
+
+    pub async fn list_directory(&self, dir_path: &str) -> Result<Vec<FileInfo>> {
+        log::info!("Listing directory: {}", dir_path);
+
+        let entries = self.backend.read_dir(Path::new(dir_path)).await?;
+        let mut files = Vec::new();
+
+        for entry in entries {
+            let metadata = self.backend.metadata(&entry.path).await?
+                .ok_or_else(|| anyhow!("Failed to read metadata for '{}'", entry.path.display()))?;
+            files.push(self.file_info_from(&entry.path, &metadata).await);
+        }
+
+        // Sort by name
+        files.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(files)
+    }
+
+    pub async fn get_directory_tree(&self, dir_path: &str, max_depth: Option<usize>) -> Result<DirectoryTree> {
+        self.get_directory_tree_with_options(dir_path, max_depth, true, &[]).await
+    }
+
+    /// Same as `get_directory_tree`, but lets the caller turn off
+    /// `.gitignore` filtering or force-include specific paths that would
+    /// otherwise be skipped (e.g. a `target/` the user explicitly opened).
+    pub async fn get_directory_tree_with_options(
+        &self,
+        dir_path: &str,
+        max_depth: Option<usize>,
+        respect_gitignore: bool,
+        overrides: &[String],
+    ) -> Result<DirectoryTree> {
+        log::info!("Building directory tree for: {}", dir_path);
+
+        let path = Path::new(dir_path);
+        if self.backend.metadata(path).await?.is_none() {
+            return Err(anyhow!("Directory '{}' does not exist", dir_path));
+        }
+
+        let overrides: Vec<PathBuf> = overrides.iter().map(PathBuf::from).collect();
+        let ignore_tree = GitIgnoreTree::new();
+        self.build_tree_recursive(path, path, max_depth.unwrap_or(5), 0, respect_gitignore, &ignore_tree, &overrides).await
+    }
+
+    fn build_tree_recursive<'a>(
+        &'a self,
+        root: &'a Path,
+        path: &'a Path,
+        max_depth: usize,
+        current_depth: usize,
+        respect_gitignore: bool,
+        ignore_tree: &'a GitIgnoreTree,
+        overrides: &'a [PathBuf],
+    ) -> Pin<Box<dyn Future<Output = Result<DirectoryTree>> + Send + 'a>> {
+        Box::pin(async move {
+            let metadata = self.backend.metadata(path).await?
+                .ok_or_else(|| anyhow!("Failed to read metadata for '{}'", path.display()))?;
+
+            let mut tree = DirectoryTree {
+                path: path.to_string_lossy().to_string(),
+                name: path.file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string(),
+                is_directory: metadata.is_dir,
+                children: Vec::new(),
+            };
+
+            if metadata.is_dir && current_depth < max_depth {
+                if let Ok(entries) = self.backend.read_dir(path).await {
+                    for entry in entries {
+                        // Skip hidden files and directories
+                        if entry.file_name.starts_with('.') {
+                            continue;
+                        }
+
+                        if respect_gitignore {
+                            let entry_is_dir = self.backend.metadata(&entry.path).await
+                                .ok()
+                                .flatten()
+                                .map(|meta| meta.is_dir)
+                                .unwrap_or(false);
+                            if ignore_tree.is_ignored(self.backend.as_ref(), root, &entry.path, entry_is_dir, overrides).await {
+                                continue;
+                            }
+                        }
+
+                        if let Ok(child_tree) = self.build_tree_recursive(
+                            root, &entry.path, max_depth, current_depth + 1, respect_gitignore, ignore_tree, overrides,
+                        ).await {
+                            tree.children.push(child_tree);
+                        }
+                    }
+
+                    // Sort children by name
+                    tree.children.sort_by(|a, b| a.name.cmp(&b.name));
+                }
+            }
+
+            Ok(tree)
+        })
+    }
+
+    pub async fn create_directory(&self, dir_path: &str) -> Result<()> {
+        log::info!("Creating directory: {}", dir_path);
+        self.backend.create_dir(Path::new(dir_path)).await
+    }
+
+    pub async fn delete_file(&self, file_path: &str) -> Result<()> {
+        log::info!("Deleting file: {}", file_path);
+        self.backend.remove(Path::new(file_path)).await
+    }
+
+    pub async fn copy_file(&self, source: &str, destination: &str) -> Result<()> {
+        log::info!("Copying file: {} -> {}", source, destination);
+        self.backend.copy(Path::new(source), Path::new(destination)).await
+    }
+
+    pub async fn move_file(&self, source: &str, destination: &str) -> Result<()> {
+        log::info!("Moving file: {} -> {}", source, destination);
+        self.backend.rename(Path::new(source), Path::new(destination)).await
+    }
+
+    /// Resolves `source`'s file name against `destination_dir`, for the
+    /// batch copy/move variants below.
+    fn batch_destination(source: &str, destination_dir: &str) -> Result<PathBuf> {
+        let file_name = Path::new(source).file_name()
+            .ok_or_else(|| anyhow!("'{}' has no file name", source))?;
+        Ok(Path::new(destination_dir).join(file_name))
+    }
+
+    /// Copies each of `sources` into `destination_dir`, one Tauri call for
+    /// a whole multi-selection instead of one per file. A failure on one
+    /// item doesn't abort the rest; its slot in the result vector carries
+    /// the error instead.
+    pub async fn copy_files(&self, sources: Vec<String>, destination_dir: &str) -> Vec<Result<(), String>> {
+        let mut results = Vec::with_capacity(sources.len());
+        for source in sources {
+            let result = async {
+                let destination = Self::batch_destination(&source, destination_dir)?;
+                self.copy_file(&source, &destination.to_string_lossy()).await
+            }.await;
+            results.push(result.map_err(|e| e.to_string()));
+        }
+        results
+    }
+
+    /// Moves each of `sources` into `destination_dir`. See `copy_files` for
+    /// the partial-failure contract.
+    pub async fn move_files(&self, sources: Vec<String>, destination_dir: &str) -> Vec<Result<(), String>> {
+        let mut results = Vec::with_capacity(sources.len());
+        for source in sources {
+            let result = async {
+                let destination = Self::batch_destination(&source, destination_dir)?;
+                self.move_file(&source, &destination.to_string_lossy()).await
+            }.await;
+            results.push(result.map_err(|e| e.to_string()));
+        }
+        results
+    }
+
+    /// Deletes each of `paths`. See `copy_files` for the partial-failure
+    /// contract.
+    pub async fn delete_files(&self, paths: Vec<String>) -> Vec<Result<(), String>> {
+        let mut results = Vec::with_capacity(paths.len());
+        for path in paths {
+            results.push(self.delete_file(&path).await.map_err(|e| e.to_string()));
+        }
+        results
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn collect_matches<'a>(
+        &'a self,
+        root: &'a Path,
+        dir: &'a Path,
+        pattern_lower: &'a str,
+        max: usize,
+        depth: usize,
+        respect_gitignore: bool,
+        ignore_tree: &'a GitIgnoreTree,
+        overrides: &'a [PathBuf],
+        results: &'a mut Vec<FileInfo>,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            if results.len() >= max || depth >= MAX_SEARCH_DEPTH {
+                return Ok(());
+            }
+
+            let entries = self.backend.read_dir(dir).await?;
+            for entry in entries {
+                if results.len() >= max {
+                    break;
+                }
+
+                let metadata = match self.backend.metadata(&entry.path).await? {
+                    Some(metadata) => metadata,
+                    None => continue,
+                };
+
+                if respect_gitignore
+                    && ignore_tree.is_ignored(self.backend.as_ref(), root, &entry.path, metadata.is_dir, overrides).await
+                {
+                    continue;
+                }
+
+                if metadata.is_dir {
+                    self.collect_matches(
+                        root, &entry.path, pattern_lower, max, depth + 1, respect_gitignore, ignore_tree, overrides, results,
+                    ).await?;
+                } else if entry.file_name.to_lowercase().contains(pattern_lower) {
+                    results.push(self.file_info_from(&entry.path, &metadata).await);
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    pub async fn search_files(&self, dir_path: &str, pattern: &str, max_results: Option<usize>) -> Result<Vec<FileInfo>> {
+        self.search_files_with_options(dir_path, pattern, max_results, true, &[]).await
+    }
+
+    /// Same as `search_files`, but lets the caller turn off `.gitignore`
+    /// filtering or force-include specific paths.
+    pub async fn search_files_with_options(
+        &self,
+        dir_path: &str,
+        pattern: &str,
+        max_results: Option<usize>,
+        respect_gitignore: bool,
+        overrides: &[String],
+    ) -> Result<Vec<FileInfo>> {
+        log::info!("Searching files in '{}' with pattern '{}'", dir_path, pattern);
+
+        let max = max_results.unwrap_or(100);
+        let pattern_lower = pattern.to_lowercase();
+        let overrides: Vec<PathBuf> = overrides.iter().map(PathBuf::from).collect();
+        let ignore_tree = GitIgnoreTree::new();
+        let mut results = Vec::new();
+        let root = Path::new(dir_path);
+        self.collect_matches(
+            root, root, &pattern_lower, max, 0, respect_gitignore, &ignore_tree, &overrides, &mut results,
+        ).await?;
+
+        log::info!("Found {} files matching pattern '{}'", results.len(), pattern);
+        Ok(results)
+    }
+
+    pub async fn get_file_info(&self, file_path: &str) -> Result<FileInfo> {
+        let path = Path::new(file_path);
+        let metadata = self.backend.metadata(path).await?
+            .ok_or_else(|| anyhow!("Failed to read metadata for '{}': not found", file_path))?;
+
+        Ok(self.file_info_from(path, &metadata).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::MemoryFs;
+
+    fn manager() -> FileManager {
+        FileManager::with_backend(Box::new(MemoryFs::new()))
+    }
+
+    #[tokio::test]
+    async fn write_then_read_round_trips_content_and_line_ending() {
+        let manager = manager();
+        manager.write_file("/notes.txt", "line one\nline two", None).await.unwrap();
+
+        let content = manager.read_file("/notes.txt").await.unwrap();
+        assert_eq!(content.text, "line one\nline two");
+        assert_eq!(content.line_ending, LineEnding::Lf);
+    }
+
+    #[tokio::test]
+    async fn write_file_preserves_existing_crlf_convention() {
+        let manager = manager();
+        manager.write_file("/notes.txt", "a\r\nb", Some(LineEnding::Crlf)).await.unwrap();
+
+        // No explicit line ending this time - should stick with the file's own.
+        manager.write_file("/notes.txt", "a\nb\nc", None).await.unwrap();
+
+        let content = manager.read_file("/notes.txt").await.unwrap();
+        assert_eq!(content.line_ending, LineEnding::Crlf);
+        assert_eq!(content.text, "a\nb\nc");
+    }
+
+    #[tokio::test]
+    async fn list_directory_reports_written_files() {
+        let manager = manager();
+        manager.write_file("/project/a.txt", "a", None).await.unwrap();
+        manager.write_file("/project/b.txt", "b", None).await.unwrap();
+
+        let mut names: Vec<String> = manager.list_directory("/project").await.unwrap()
+            .into_iter().map(|info| info.name).collect();
+        names.sort();
+        assert_eq!(names, vec!["a.txt".to_string(), "b.txt".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn copy_then_move_then_delete() {
+        let manager = manager();
+        manager.write_file("/src.txt", "hello", None).await.unwrap();
+
+        manager.copy_file("/src.txt", "/copy.txt").await.unwrap();
+        assert_eq!(manager.read_file("/copy.txt").await.unwrap().text, "hello");
+        assert_eq!(manager.read_file("/src.txt").await.unwrap().text, "hello");
+
+        manager.move_file("/copy.txt", "/moved.txt").await.unwrap();
+        assert!(manager.read_file("/copy.txt").await.is_err());
+        assert_eq!(manager.read_file("/moved.txt").await.unwrap().text, "hello");
+
+        manager.delete_file("/moved.txt").await.unwrap();
+        assert!(manager.read_file("/moved.txt").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn read_file_missing_path_is_an_error() {
+        let manager = manager();
+        assert!(manager.read_file("/does/not/exist.txt").await.is_err());
+    }
+}
@@ -0,0 +1,331 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::RwLock;
+
+/// Backend-agnostic file metadata. `modified` is best-effort — some backends
+/// (and `MemoryFs`) don't track it, so callers should treat `None` as
+/// "unknown" rather than "never modified".
+#[derive(Debug, Clone)]
+pub struct FsMetadata {
+    pub len: u64,
+    pub is_dir: bool,
+    pub modified: Option<std::time::SystemTime>,
+}
+
+/// One entry yielded by `Fs::read_dir`.
+#[derive(Debug, Clone)]
+pub struct DirEntry {
+    pub path: PathBuf,
+    pub file_name: String,
+}
+
+/// Governs `Fs::write_atomic`'s write-temp-then-rename behavior.
+#[derive(Debug, Clone, Copy)]
+pub struct AtomicWriteOptions {
+    /// Whether to `fsync` the temp file before renaming it into place. Costs
+    /// a disk flush per write; disable for bulk/throwaway writes where a
+    /// torn file on crash is acceptable.
+    pub fsync: bool,
+    /// Whether to carry the destination's existing permissions over to the
+    /// replacement file, rather than leaving it at the process's default
+    /// umask.
+    pub preserve_mode: bool,
+}
+
+impl Default for AtomicWriteOptions {
+    fn default() -> Self {
+        Self { fsync: true, preserve_mode: true }
+    }
+}
+
+/// Storage backend abstraction so `FileManager` can run against a real
+/// filesystem, an in-memory tree for tests, or — eventually — a remote or
+/// object-store backend without its call sites changing.
+#[async_trait]
+pub trait Fs: Send + Sync {
+    async fn read(&self, path: &Path) -> Result<String>;
+    async fn write(&self, path: &Path, content: &str) -> Result<()>;
+    /// Writes `content` to `path` so a crash or power loss never leaves a
+    /// half-written file: the content lands in a sibling temp file first,
+    /// which is renamed over `path` only once it's fully on disk.
+    async fn write_atomic(&self, path: &Path, content: &str, options: &AtomicWriteOptions) -> Result<()>;
+    async fn create_dir(&self, path: &Path) -> Result<()>;
+    async fn remove(&self, path: &Path) -> Result<()>;
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()>;
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()>;
+    /// `Ok(None)` if `path` doesn't exist; any other failure is a real error.
+    async fn metadata(&self, path: &Path) -> Result<Option<FsMetadata>>;
+    async fn read_dir(&self, path: &Path) -> Result<Vec<DirEntry>>;
+    async fn canonicalize(&self, path: &Path) -> Result<PathBuf>;
+}
+
+/// `Fs` backed by the real filesystem via `tokio::fs`, matching the behavior
+/// `FileManager` had before it was factored behind this trait.
+pub struct LocalFs;
+
+#[async_trait]
+impl Fs for LocalFs {
+    async fn read(&self, path: &Path) -> Result<String> {
+        fs::read_to_string(path).await
+            .map_err(|e| anyhow!("Failed to read '{}': {}", path.display(), e))
+    }
+
+    async fn write(&self, path: &Path, content: &str) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await
+                .map_err(|e| anyhow!("Failed to create directories for '{}': {}", path.display(), e))?;
+        }
+        fs::write(path, content).await
+            .map_err(|e| anyhow!("Failed to write '{}': {}", path.display(), e))
+    }
+
+    async fn write_atomic(&self, path: &Path, content: &str, options: &AtomicWriteOptions) -> Result<()> {
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        fs::create_dir_all(parent).await
+            .map_err(|e| anyhow!("Failed to create directories for '{}': {}", path.display(), e))?;
+
+        let existing_permissions = if options.preserve_mode {
+            fs::metadata(path).await.ok().map(|meta| meta.permissions())
+        } else {
+            None
+        };
+
+        let file_name = path.file_name()
+            .ok_or_else(|| anyhow!("'{}' has no file name", path.display()))?
+            .to_string_lossy();
+        let temp_path = parent.join(format!(".{}.{}.tmp", file_name, uuid::Uuid::new_v4()));
+
+        let write_result: Result<()> = async {
+            let mut file = fs::File::create(&temp_path).await
+                .map_err(|e| anyhow!("Failed to create temp file '{}': {}", temp_path.display(), e))?;
+            file.write_all(content.as_bytes()).await
+                .map_err(|e| anyhow!("Failed to write temp file '{}': {}", temp_path.display(), e))?;
+            if options.fsync {
+                file.sync_all().await
+                    .map_err(|e| anyhow!("Failed to fsync temp file '{}': {}", temp_path.display(), e))?;
+            }
+            Ok(())
+        }.await;
+
+        if let Err(e) = write_result {
+            let _ = fs::remove_file(&temp_path).await;
+            return Err(e);
+        }
+
+        if let Some(permissions) = existing_permissions {
+            if let Err(e) = fs::set_permissions(&temp_path, permissions).await {
+                let _ = fs::remove_file(&temp_path).await;
+                return Err(anyhow!("Failed to set permissions on '{}': {}", temp_path.display(), e));
+            }
+        }
+
+        fs::rename(&temp_path, path).await
+            .map_err(|e| anyhow!("Failed to rename '{}' to '{}': {}", temp_path.display(), path.display(), e))
+    }
+
+    async fn create_dir(&self, path: &Path) -> Result<()> {
+        fs::create_dir_all(path).await
+            .map_err(|e| anyhow!("Failed to create directory '{}': {}", path.display(), e))
+    }
+
+    async fn remove(&self, path: &Path) -> Result<()> {
+        let metadata = fs::metadata(path).await
+            .map_err(|e| anyhow!("Failed to read metadata for '{}': {}", path.display(), e))?;
+        let result = if metadata.is_dir() {
+            fs::remove_dir_all(path).await
+        } else {
+            fs::remove_file(path).await
+        };
+        result.map_err(|e| anyhow!("Failed to remove '{}': {}", path.display(), e))
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent).await
+                .map_err(|e| anyhow!("Failed to create directories for '{}': {}", to.display(), e))?;
+        }
+        fs::rename(from, to).await
+            .map_err(|e| anyhow!("Failed to rename '{}' to '{}': {}", from.display(), to.display(), e))
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent).await
+                .map_err(|e| anyhow!("Failed to create directories for '{}': {}", to.display(), e))?;
+        }
+        fs::copy(from, to).await
+            .map(|_| ())
+            .map_err(|e| anyhow!("Failed to copy '{}' to '{}': {}", from.display(), to.display(), e))
+    }
+
+    async fn metadata(&self, path: &Path) -> Result<Option<FsMetadata>> {
+        match fs::metadata(path).await {
+            Ok(meta) => Ok(Some(FsMetadata {
+                len: meta.len(),
+                is_dir: meta.is_dir(),
+                modified: meta.modified().ok(),
+            })),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(anyhow!("Failed to read metadata for '{}': {}", path.display(), e)),
+        }
+    }
+
+    async fn read_dir(&self, path: &Path) -> Result<Vec<DirEntry>> {
+        let mut entries = fs::read_dir(path).await
+            .map_err(|e| anyhow!("Failed to read directory '{}': {}", path.display(), e))?;
+
+        let mut results = Vec::new();
+        while let Some(entry) = entries.next_entry().await
+            .map_err(|e| anyhow!("Failed to read directory entry: {}", e))?
+        {
+            results.push(DirEntry {
+                path: entry.path(),
+                file_name: entry.file_name().to_string_lossy().to_string(),
+            });
+        }
+        Ok(results)
+    }
+
+    async fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
+        fs::canonicalize(path).await
+            .map_err(|e| anyhow!("Failed to canonicalize '{}': {}", path.display(), e))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum MemoryNode {
+    File(String),
+    Dir,
+}
+
+/// In-memory `Fs` backend for unit tests: a flat map keyed by normalized
+/// path, with directories tracked as their own entries so `read_dir` and
+/// `metadata` behave like a real tree without touching disk.
+pub struct MemoryFs {
+    nodes: RwLock<HashMap<PathBuf, MemoryNode>>,
+}
+
+impl MemoryFs {
+    pub fn new() -> Self {
+        Self {
+            nodes: RwLock::new(HashMap::from([(PathBuf::from("/"), MemoryNode::Dir)])),
+        }
+    }
+
+    fn ensure_dir(nodes: &mut HashMap<PathBuf, MemoryNode>, path: &Path) {
+        let mut current = PathBuf::new();
+        for component in path.components() {
+            current.push(component);
+            nodes.entry(current.clone()).or_insert(MemoryNode::Dir);
+        }
+    }
+}
+
+impl Default for MemoryFs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Fs for MemoryFs {
+    async fn read(&self, path: &Path) -> Result<String> {
+        match self.nodes.read().await.get(path) {
+            Some(MemoryNode::File(content)) => Ok(content.clone()),
+            Some(MemoryNode::Dir) => Err(anyhow!("'{}' is a directory", path.display())),
+            None => Err(anyhow!("'{}' does not exist", path.display())),
+        }
+    }
+
+    async fn write(&self, path: &Path, content: &str) -> Result<()> {
+        let mut nodes = self.nodes.write().await;
+        if let Some(parent) = path.parent() {
+            Self::ensure_dir(&mut nodes, parent);
+        }
+        nodes.insert(path.to_path_buf(), MemoryNode::File(content.to_string()));
+        Ok(())
+    }
+
+    async fn write_atomic(&self, path: &Path, content: &str, _options: &AtomicWriteOptions) -> Result<()> {
+        // A `HashMap` insert is already atomic from callers' perspective, so
+        // there's no torn-write state to guard against here.
+        self.write(path, content).await
+    }
+
+    async fn create_dir(&self, path: &Path) -> Result<()> {
+        let mut nodes = self.nodes.write().await;
+        Self::ensure_dir(&mut nodes, path);
+        Ok(())
+    }
+
+    async fn remove(&self, path: &Path) -> Result<()> {
+        let mut nodes = self.nodes.write().await;
+        if nodes.remove(path).is_none() {
+            return Err(anyhow!("'{}' does not exist", path.display()));
+        }
+        let children: Vec<PathBuf> = nodes.keys().filter(|p| p.starts_with(path)).cloned().collect();
+        for child in children {
+            nodes.remove(&child);
+        }
+        Ok(())
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        let mut nodes = self.nodes.write().await;
+        let node = nodes.remove(from).ok_or_else(|| anyhow!("'{}' does not exist", from.display()))?;
+        if let Some(parent) = to.parent() {
+            Self::ensure_dir(&mut nodes, parent);
+        }
+        nodes.insert(to.to_path_buf(), node);
+        Ok(())
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        let mut nodes = self.nodes.write().await;
+        let node = nodes.get(from).cloned().ok_or_else(|| anyhow!("'{}' does not exist", from.display()))?;
+        if let Some(parent) = to.parent() {
+            Self::ensure_dir(&mut nodes, parent);
+        }
+        nodes.insert(to.to_path_buf(), node);
+        Ok(())
+    }
+
+    async fn metadata(&self, path: &Path) -> Result<Option<FsMetadata>> {
+        Ok(self.nodes.read().await.get(path).map(|node| match node {
+            MemoryNode::File(content) => FsMetadata { len: content.len() as u64, is_dir: false, modified: None },
+            MemoryNode::Dir => FsMetadata { len: 0, is_dir: true, modified: None },
+        }))
+    }
+
+    async fn read_dir(&self, path: &Path) -> Result<Vec<DirEntry>> {
+        let nodes = self.nodes.read().await;
+        if !matches!(nodes.get(path), Some(MemoryNode::Dir)) {
+            return Err(anyhow!("'{}' is not a directory", path.display()));
+        }
+        let mut results = Vec::new();
+        for child_path in nodes.keys() {
+            if child_path.parent() == Some(path) {
+                results.push(DirEntry {
+                    path: child_path.clone(),
+                    file_name: child_path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                });
+            }
+        }
+        Ok(results)
+    }
+
+    async fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
+        if self.nodes.read().await.contains_key(path) {
+            Ok(path.to_path_buf())
+        } else {
+            Err(anyhow!("'{}' does not exist", path.display()))
+        }
+    }
+}
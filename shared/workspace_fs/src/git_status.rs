@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex as StdMutex};
+
+use anyhow::{Result, anyhow};
+use git2::Repository;
+use git_status_classify::classify_status;
+use tokio::sync::Mutex;
+
+type SharedRepo = Arc<StdMutex<Repository>>;
+
+/// Discovers and caches the git repository enclosing a given path, so
+/// listing the same directory repeatedly doesn't re-walk the filesystem for
+/// a `.git` on every call. Keyed by the repository's working directory, with
+/// a `None` entry recorded for paths that turned out not to be in a
+/// repository at all.
+#[derive(Default)]
+pub struct GitRepoCache {
+    repos: Mutex<HashMap<PathBuf, Option<SharedRepo>>>,
+}
+
+impl GitRepoCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    async fn repo_for(&self, path: &Path) -> Option<SharedRepo> {
+        {
+            let repos = self.repos.lock().await;
+            let mut current = Some(path);
+            while let Some(dir) = current {
+                if let Some(entry) = repos.get(dir) {
+                    return entry.clone();
+                }
+                current = dir.parent();
+            }
+        }
+
+        let discovered = Repository::discover(path).ok();
+        let workdir_root = discovered.as_ref().and_then(|repo| repo.workdir()).map(|p| p.to_path_buf());
+        let shared = discovered.map(|repo| Arc::new(StdMutex::new(repo)));
+
+        let mut repos = self.repos.lock().await;
+        repos.insert(workdir_root.unwrap_or_else(|| path.to_path_buf()), shared.clone());
+        shared
+    }
+
+    /// `"added"`/`"modified"`/`"untracked"`/`"clean"`, or `None` if `path`
+    /// isn't inside a git working tree.
+    pub async fn status_for(&self, path: &Path) -> Option<String> {
+        let repo = self.repo_for(path).await?;
+        let repo = repo.lock().unwrap();
+        let workdir = repo.workdir()?;
+        let rel = path.strip_prefix(workdir).ok()?;
+        let status = repo.status_file(rel).ok()?;
+        Some(classify_status(status))
+    }
+
+    /// The file's committed `HEAD` blob content.
+    pub async fn head_content(&self, path: &Path) -> Result<String> {
+        let repo = self.repo_for(path).await
+            .ok_or_else(|| anyhow!("'{}' is not inside a git repository", path.display()))?;
+        let repo = repo.lock().unwrap();
+        let workdir = repo.workdir()
+            .ok_or_else(|| anyhow!("repository for '{}' has no working directory", path.display()))?;
+        let rel = path.strip_prefix(workdir)
+            .map_err(|_| anyhow!("'{}' is outside its repository's working directory", path.display()))?;
+
+        let head_tree = repo.head()
+            .and_then(|head| head.peel_to_tree())
+            .map_err(|e| anyhow!("Failed to resolve HEAD tree: {}", e))?;
+        let entry = head_tree.get_path(rel)
+            .map_err(|e| anyhow!("'{}' has no committed version at HEAD: {}", path.display(), e))?;
+        let blob = entry.to_object(&repo)
+            .and_then(|object| object.peel_to_blob())
+            .map_err(|e| anyhow!("Failed to read HEAD blob for '{}': {}", path.display(), e))?;
+
+        String::from_utf8(blob.content().to_vec())
+            .map_err(|e| anyhow!("HEAD content of '{}' is not valid UTF-8: {}", path.display(), e))
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use glob_match::glob_match;
+
+use crate::fs::Fs;
+
+/// One line out of a `.gitignore` file, already split into its pattern and
+/// modifiers. Matching follows the common subset of gitignore semantics:
+/// `*`/`**`/`?` wildcards, `!` negation, a trailing `/` for directory-only
+/// rules, and patterns containing a `/` being anchored to the directory the
+/// `.gitignore` lives in rather than matching at any depth.
+#[derive(Debug, Clone)]
+struct IgnoreRule {
+    pattern: String,
+    negated: bool,
+    dir_only: bool,
+    anchored: bool,
+}
+
+impl IgnoreRule {
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let mut pattern = line;
+        let negated = match pattern.strip_prefix('!') {
+            Some(rest) => {
+                pattern = rest;
+                true
+            }
+            None => false,
+        };
+        let dir_only = match pattern.strip_suffix('/') {
+            Some(rest) => {
+                pattern = rest;
+                true
+            }
+            None => false,
+        };
+        let pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+        if pattern.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            anchored: pattern.contains('/'),
+            pattern: pattern.to_string(),
+            negated,
+            dir_only,
+        })
+    }
+
+    fn matches(&self, rel_path: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        if self.anchored {
+            return glob_match(&self.pattern, rel_path);
+        }
+        let name = rel_path.rsplit('/').next().unwrap_or(rel_path);
+        glob_match(&self.pattern, name) || glob_match(&self.pattern, rel_path)
+    }
+}
+
+/// Answers "is this path ignored?" for one directory walk by lazily loading
+/// and caching the `.gitignore` of every directory visited, then consulting
+/// the chain from the walk root down to the entry's parent — nearer rules
+/// override farther ones, matching how `git status` resolves nested
+/// `.gitignore` files. Scoped to a single `build_tree_recursive`/
+/// `collect_matches` call rather than kept on `FileManager`, since the cache
+/// is only ever useful for the walk that populated it.
+#[derive(Default)]
+pub struct GitIgnoreTree {
+    cache: tokio::sync::RwLock<HashMap<PathBuf, Vec<IgnoreRule>>>,
+}
+
+impl GitIgnoreTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    async fn rules_for(&self, backend: &dyn Fs, dir: &Path) -> Vec<IgnoreRule> {
+        if let Some(rules) = self.cache.read().await.get(dir) {
+            return rules.clone();
+        }
+
+        let rules = match backend.read(&dir.join(".gitignore")).await {
+            Ok(content) => content.lines().filter_map(IgnoreRule::parse).collect(),
+            Err(_) => Vec::new(),
+        };
+        self.cache.write().await.insert(dir.to_path_buf(), rules.clone());
+        rules
+    }
+
+    /// Whether `path` (of type `is_dir`, a descendant of `root`) is ignored
+    /// by `root` or any ancestor's `.gitignore` between them, unless it
+    /// appears in `overrides`.
+    pub async fn is_ignored(
+        &self,
+        backend: &dyn Fs,
+        root: &Path,
+        path: &Path,
+        is_dir: bool,
+        overrides: &[PathBuf],
+    ) -> bool {
+        if overrides.iter().any(|o| o == path) {
+            return false;
+        }
+
+        // Walk root -> entry's parent, collecting every directory in between.
+        let mut chain = vec![root.to_path_buf()];
+        if let Some(parent) = path.parent() {
+            if parent != root {
+                let mut segment = root.to_path_buf();
+                if let Ok(rel) = parent.strip_prefix(root) {
+                    for component in rel.components() {
+                        segment.push(component);
+                        chain.push(segment.clone());
+                    }
+                }
+            }
+        }
+
+        let mut ignored = false;
+        for dir in &chain {
+            let rules = self.rules_for(backend, dir).await;
+            let rel = path.strip_prefix(dir).unwrap_or(path);
+            let rel_str = rel.to_string_lossy();
+            for rule in &rules {
+                if rule.matches(&rel_str, is_dir) {
+                    ignored = !rule.negated;
+                }
+            }
+        }
+
+        ignored
+    }
+}
@@ -0,0 +1,12 @@
+//! Async filesystem abstraction, `.gitignore`-aware traversal, git status
+//! lookups, and `FileManager` built on top of them — shared by every crate
+//! that needs to read/write project files, since `FileManager` previously
+//! only existed in `src-tauri`'s orphaned tree and was unreachable from
+//! `claudeditor_backup`'s Tauri commands.
+
+pub mod fs;
+pub mod gitignore;
+pub mod git_status;
+pub mod file_manager;
+
+pub use file_manager::{DirectoryTree, FileContent, FileInfo, FileManager, LineEnding};
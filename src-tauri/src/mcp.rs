@@ -1,323 +0,0 @@
-use std::collections::HashMap;
-use serde::{Deserialize, Serialize};
-use anyhow::{Result, anyhow};
-use tokio::sync::RwLock;
-use std::sync::Arc;
-use uuid::Uuid;
-use chrono::{DateTime, Utc};
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MCPService {
-    pub id: String,
-    pub name: String,
-    pub endpoint: String,
-    pub status: MCPServiceStatus,
-    pub capabilities: Vec<String>,
-    pub last_heartbeat: DateTime<Utc>,
-    pub metadata: HashMap<String, String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum MCPServiceStatus {
-    Online,
-    Offline,
-    Error,
-    Connecting,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MCPMessage {
-    pub id: String,
-    pub message_type: MCPMessageType,
-    pub source: String,
-    pub target: String,
-    pub payload: serde_json::Value,
-    pub timestamp: DateTime<Utc>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum MCPMessageType {
-    Request,
-    Response,
-    Event,
-    Heartbeat,
-    Registration,
-    Deregistration,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MCPTool {
-    pub id: String,
-    pub name: String,
-    pub description: String,
-    pub version: String,
-    pub service_id: String,
-    pub capabilities: Vec<String>,
-    pub schema: serde_json::Value,
-}
-
-#[derive(Debug, Clone)]
-pub struct MCPCoordinator {
-    services: Arc<RwLock<HashMap<String, MCPService>>>,
-    tools: Arc<RwLock<HashMap<String, MCPTool>>>,
-    message_queue: Arc<RwLock<Vec<MCPMessage>>>,
-    port: u16,
-}
-
-impl MCPCoordinator {
-    pub async fn new() -> Result<Self> {
-        log::info!("Initializing MCP Coordinator...");
-        
-        let coordinator = Self {
-            services: Arc::new(RwLock::new(HashMap::new())),
-            tools: Arc::new(RwLock::new(HashMap::new())),
-            message_queue: Arc::new(RwLock::new(Vec::new())),
-            port: 8080,
-        };
-        
-        // Initialize default services
-        coordinator.initialize_default_services().await?;
-        
-        log::info!("MCP Coordinator initialized successfully");
-        Ok(coordinator)
-    }
-    
-    async fn initialize_default_services(&self) -> Result<()> {
-        log::info!("Initializing default MCP services...");
-        
-        let mut services = self.services.write().await;
-        let mut tools = self.tools.write().await;
-        
-        // Stagewise MCP Service
-        let stagewise_service = MCPService {
-            id: "stagewise_mcp".to_string(),
-            name: "Stagewise Visual Programming".to_string(),
-            endpoint: "mcp://localhost:8081/stagewise".to_string(),
-            status: MCPServiceStatus::Online,
-            capabilities: vec![
-                "visual_programming".to_string(),
-                "workflow_automation".to_string(),
-                "ui_generation".to_string(),
-            ],
-            last_heartbeat: Utc::now(),
-            metadata: HashMap::new(),
-        };
-        
-        services.insert(stagewise_service.id.clone(), stagewise_service.clone());
-        
-        // Stagewise Tools
-        tools.insert("visual_editor".to_string(), MCPTool {
-            id: "visual_editor".to_string(),
-            name: "Visual Editor".to_string(),
-            description: "Drag-and-drop visual programming interface".to_string(),
-            version: "1.0.0".to_string(),
-            service_id: stagewise_service.id.clone(),
-            capabilities: vec!["create_workflow".to_string(), "edit_workflow".to_string()],
-            schema: serde_json::json!({
-                "type": "object",
-                "properties": {
-                    "workflow_id": {"type": "string"},
-                    "nodes": {"type": "array"},
-                    "connections": {"type": "array"}
-                }
-            }),
-        });
-        
-        // AG-UI MCP Service
-        let ag_ui_service = MCPService {
-            id: "ag_ui_mcp".to_string(),
-            name: "AG-UI Component Generator".to_string(),
-            endpoint: "mcp://localhost:8082/ag_ui".to_string(),
-            status: MCPServiceStatus::Online,
-            capabilities: vec![
-                "ui_generation".to_string(),
-                "component_creation".to_string(),
-                "interaction_design".to_string(),
-            ],
-            last_heartbeat: Utc::now(),
-            metadata: HashMap::new(),
-        };
-        
-        services.insert(ag_ui_service.id.clone(), ag_ui_service.clone());
-        
-        // AG-UI Tools
-        tools.insert("component_generator".to_string(), MCPTool {
-            id: "component_generator".to_string(),
-            name: "Component Generator".to_string(),
-            description: "Generate UI components from natural language descriptions".to_string(),
-            version: "1.0.0".to_string(),
-            service_id: ag_ui_service.id.clone(),
-            capabilities: vec!["generate_component".to_string(), "customize_component".to_string()],
-            schema: serde_json::json!({
-                "type": "object",
-                "properties": {
-                    "description": {"type": "string"},
-                    "component_type": {"type": "string"},
-                    "style_preferences": {"type": "object"}
-                }
-            }),
-        });
-        
-        // Agent Zero MCP Service
-        let agent_zero_service = MCPService {
-            id: "agent_zero_mcp".to_string(),
-            name: "Agent Zero Organic Intelligence".to_string(),
-            endpoint: "mcp://localhost:8083/agent_zero".to_string(),
-            status: MCPServiceStatus::Online,
-            capabilities: vec![
-                "organic_learning".to_string(),
-                "adaptive_behavior".to_string(),
-                "autonomous_operation".to_string(),
-            ],
-            last_heartbeat: Utc::now(),
-            metadata: HashMap::new(),
-        };
-        
-        services.insert(agent_zero_service.id.clone(), agent_zero_service.clone());
-        
-        // Agent Zero Tools
-        tools.insert("organic_agent".to_string(), MCPTool {
-            id: "organic_agent".to_string(),
-            name: "Organic Agent".to_string(),
-            description: "Self-learning AI agent with adaptive capabilities".to_string(),
-            version: "1.0.0".to_string(),
-            service_id: agent_zero_service.id.clone(),
-            capabilities: vec!["learn".to_string(), "adapt".to_string(), "execute".to_string()],
-            schema: serde_json::json!({
-                "type": "object",
-                "properties": {
-                    "task": {"type": "string"},
-                    "context": {"type": "object"},
-                    "learning_mode": {"type": "boolean"}
-                }
-            }),
-        });
-        
-        log::info!("Initialized {} services and {} tools", services.len(), tools.len());
-        Ok(())
-    }
-    
-    pub async fn register_service(&self, service: MCPService) -> Result<()> {
-        log::info!("Registering MCP service: {}", service.name);
-        
-        let mut services = self.services.write().await;
-        services.insert(service.id.clone(), service);
-        
-        Ok(())
-    }
-    
-    pub async fn deregister_service(&self, service_id: &str) -> Result<()> {
-        log::info!("Deregistering MCP service: {}", service_id);
-        
-        let mut services = self.services.write().await;
-        services.remove(service_id);
-        
-        // Remove associated tools
-        let mut tools = self.tools.write().await;
-        tools.retain(|_, tool| tool.service_id != service_id);
-        
-        Ok(())
-    }
-    
-    pub async fn get_services(&self) -> Result<Vec<MCPService>> {
-        let services = self.services.read().await;
-        Ok(services.values().cloned().collect())
-    }
-    
-    pub async fn get_tools(&self) -> Result<Vec<MCPTool>> {
-        let tools = self.tools.read().await;
-        Ok(tools.values().cloned().collect())
-    }
-    
-    pub async fn discover_tools(&self) -> Result<Vec<String>> {
-        log::info!("Discovering MCP tools...");
-        
-        // This would integrate with MCP-Zero for actual tool discovery
-        // For now, return the tools we have registered
-        let tools = self.tools.read().await;
-        let tool_names: Vec<String> = tools.values()
-            .map(|tool| format!("{} ({})", tool.name, tool.service_id))
-            .collect();
-        
-        log::info!("Discovered {} tools", tool_names.len());
-        Ok(tool_names)
-    }
-    
-    pub async fn send_message(&self, message: MCPMessage) -> Result<()> {
-        log::info!("Sending MCP message: {} -> {}", message.source, message.target);
-        
-        let mut queue = self.message_queue.write().await;
-        queue.push(message);
-        
-        // In a real implementation, this would route the message to the appropriate service
-        Ok(())
-    }
-    
-    pub async fn receive_messages(&self) -> Result<Vec<MCPMessage>> {
-        let mut queue = self.message_queue.write().await;
-        let messages = queue.drain(..).collect();
-        Ok(messages)
-    }
-    
-    pub async fn health_check(&self) -> Result<HashMap<String, MCPServiceStatus>> {
-        log::info!("Performing health check on MCP services...");
-        
-        let services = self.services.read().await;
-        let mut health_status = HashMap::new();
-        
-        for (id, service) in services.iter() {
-            // In a real implementation, this would ping the service
-            health_status.insert(id.clone(), service.status.clone());
-        }
-        
-        Ok(health_status)
-    }
-    
-    pub async fn execute_tool(&self, tool_id: &str, parameters: serde_json::Value) -> Result<serde_json::Value> {
-        log::info!("Executing tool: {}", tool_id);
-        
-        let tools = self.tools.read().await;
-        let tool = tools.get(tool_id)
-            .ok_or_else(|| anyhow!("Tool '{}' not found", tool_id))?;
-        
-        // Create execution message
-        let message = MCPMessage {
-            id: Uuid::new_v4().to_string(),
-            message_type: MCPMessageType::Request,
-            source: "coordinator".to_string(),
-            target: tool.service_id.clone(),
-            payload: serde_json::json!({
-                "tool_id": tool_id,
-                "parameters": parameters
-            }),
-            timestamp: Utc::now(),
-        };
-        
-        // Send message (in real implementation, this would wait for response)
-        self.send_message(message).await?;
-        
-        // Simulate tool execution result
-        Ok(serde_json::json!({
-            "status": "success",
-            "result": format!("Tool '{}' executed successfully", tool.name),
-            "timestamp": Utc::now()
-        }))
-    }
-    
-    pub async fn get_service_capabilities(&self, service_id: &str) -> Result<Vec<String>> {
-        let services = self.services.read().await;
-        let service = services.get(service_id)
-            .ok_or_else(|| anyhow!("Service '{}' not found", service_id))?;
-        
-        Ok(service.capabilities.clone())
-    }
-    
-    pub async fn get_tool_schema(&self, tool_id: &str) -> Result<serde_json::Value> {
-        let tools = self.tools.read().await;
-        let tool = tools.get(tool_id)
-            .ok_or_else(|| anyhow!("Tool '{}' not found", tool_id))?;
-        
-        Ok(tool.schema.clone())
-    }
-}
-